@@ -31,7 +31,7 @@ pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..Default::default()
             },
             Duck1,
-            SimpleTopDownController::new(10.0),
+            SimpleTopDownController::new(600.0).with_acceleration(3000.0, 3000.0),
         ))
         .id();
     commands.spawn((
@@ -9,7 +9,7 @@ use some_bevy_tools::range;
 
 static CHANGE_PER_SECOND: f32 = 50.0;
 
-#[derive(Default)]
+#[derive(Default, TypePath)]
 struct XAxis;
 
 fn main() {
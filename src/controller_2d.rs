@@ -1,10 +1,20 @@
 //! Define some basic actions and keymapping to get started with
 //! 2D games quickly.
+//!
+//! The SimpleTopDownController moves entities with a speed in units per second.  The
+//! direction is normalized, so diagonal movement is not faster, and the controller can
+//! optionally accelerate and decelerate.  With the `bevy_rapier2d` feature, it can also
+//! drive the rapier Velocity, for example of a `physics2d::PhysicsBundle`.
+//!
+//! For local multiplayer, give each player entity its own `InputMapping<TopDownAction>`
+//! and `ActionState<TopDownAction>` component.  Controllers with an ActionState component
+//! follow it, all others follow the global ActionState resource.
 
 use crate::input::{self, InputMapping, UserButtonInput::*};
 use bevy::prelude::*;
 
 #[derive(Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TopDownAction {
     /* User wants to go up */
     MoveUp,
@@ -41,6 +51,34 @@ pub fn setup_top_down_mapping(mut commands: Commands) {
         (KeyPressed(KeyCode::Space), TopDownAction::Action),
         (KeyPressed(KeyCode::Enter), TopDownAction::Action2),
         (KeyPressed(KeyCode::Escape), TopDownAction::Exit),
+        (
+            GamepadButtonPressed(GamepadButtonType::DPadUp),
+            TopDownAction::MoveUp,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::DPadDown),
+            TopDownAction::MoveDown,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::DPadLeft),
+            TopDownAction::MoveLeft,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::DPadRight),
+            TopDownAction::MoveRight,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::South),
+            TopDownAction::Action,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::East),
+            TopDownAction::Action2,
+        ),
+        (
+            GamepadButtonPressed(GamepadButtonType::Start),
+            TopDownAction::Exit,
+        ),
     ]
     .into();
     commands.insert_resource(input_mapping);
@@ -55,59 +93,127 @@ impl Plugin for TopDownControllerPlugin {
     }
 }
 
+/// What the SimpleTopDownController moves.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TopDownDrive {
+    /// Change the translation of the Transform.
+    #[default]
+    Transform,
+
+    /// Set the linear velocity of the rapier Velocity and let the physics move the entity.
+    /// The controller continues from the Velocity, so changes by collisions are kept.
+    #[cfg(feature = "bevy_rapier2d")]
+    Velocity,
+}
+
 #[derive(Component)]
 pub struct SimpleTopDownController {
+    /// Maximum speed in units per second.
     pub speed: f32,
     pub active: bool,
+
+    /// Change of the speed per second while moving.  None reaches the speed immediately.
+    pub acceleration: Option<f32>,
+
+    /// Change of the speed per second while stopping.  None stops immediately.
+    pub deceleration: Option<f32>,
+    pub drive: TopDownDrive,
+
+    /// Current velocity in units per second.  With the Velocity drive, it is read from
+    /// the rapier Velocity every frame.
+    pub velocity: Vec2,
 }
 
 impl SimpleTopDownController {
+    /// Create a new controller which moves with the speed in units per second.
     pub fn new(speed: f32) -> Self {
         Self {
             speed,
             active: true,
+            acceleration: None,
+            deceleration: None,
+            drive: TopDownDrive::default(),
+            velocity: Vec2::ZERO,
         }
     }
-}
 
-fn simple_top_down_controller(
-    mut actions: EventReader<input::ActionEvent<TopDownAction>>,
-    mut entity_query: Query<
-        (&SimpleTopDownController, &mut Transform),
-        With<SimpleTopDownController>,
-    >,
-) {
-    for action in actions.read() {
-        match action.action {
-            TopDownAction::MoveUp => {
-                for (controller, mut transform) in entity_query.iter_mut() {
-                    if controller.active {
-                        transform.translation.y += controller.speed;
-                    }
-                }
-            }
-            TopDownAction::MoveDown => {
-                for (controller, mut transform) in entity_query.iter_mut() {
-                    if controller.active {
-                        transform.translation.y -= controller.speed;
-                    }
-                }
-            }
-            TopDownAction::MoveLeft => {
-                for (controller, mut transform) in entity_query.iter_mut() {
-                    if controller.active {
-                        transform.translation.x -= controller.speed;
-                    }
-                }
-            }
-            TopDownAction::MoveRight => {
-                for (controller, mut transform) in entity_query.iter_mut() {
-                    if controller.active {
-                        transform.translation.x += controller.speed;
-                    }
+    /// Create a new controller which accelerates and decelerates smoothly.
+    pub fn with_acceleration(self, acceleration: f32, deceleration: f32) -> Self {
+        Self {
+            acceleration: Some(acceleration),
+            deceleration: Some(deceleration),
+            ..self
+        }
+    }
+
+    /// Create a new controller which sets the rapier Velocity instead of the Transform.
+    #[cfg(feature = "bevy_rapier2d")]
+    pub fn with_physics(self) -> Self {
+        Self {
+            drive: TopDownDrive::Velocity,
+            ..self
+        }
+    }
+
+    /// Move the velocity towards the direction within the delta time.
+    fn accelerate(&mut self, direction: Vec2, delta_seconds: f32) {
+        let target = direction * self.speed;
+        let rate = if target == Vec2::ZERO {
+            self.deceleration
+        } else {
+            self.acceleration
+        };
+        self.velocity = match rate {
+            Some(rate) => {
+                let difference = target - self.velocity;
+                let step = rate * delta_seconds;
+                if difference.length() <= step {
+                    target
+                } else {
+                    self.velocity + difference.normalize() * step
                 }
             }
-            _ => {}
+            None => target,
+        };
+    }
+}
+
+/// The normalized movement direction of the pressed actions.
+fn top_down_direction(action_state: &input::ActionState<TopDownAction>) -> Vec2 {
+    let strength = |action| action_state.strength(&action);
+    Vec2::new(
+        strength(TopDownAction::MoveRight) - strength(TopDownAction::MoveLeft),
+        strength(TopDownAction::MoveUp) - strength(TopDownAction::MoveDown),
+    )
+    .clamp_length_max(1.0)
+}
+
+/// Moves the SimpleTopDownControllers.
+///
+/// Entities with their own ActionState component follow it, all others follow
+/// the ActionState resource.
+pub fn simple_top_down_controller(
+    action_state: Res<input::ActionState<TopDownAction>>,
+    mut entity_query: Query<(
+        &mut SimpleTopDownController,
+        &mut Transform,
+        Option<&input::ActionState<TopDownAction>>,
+    )>,
+    time: Res<Time>,
+) {
+    let global_direction = top_down_direction(&action_state);
+    for (mut controller, mut transform, player_action_state) in entity_query.iter_mut() {
+        let direction = if !controller.active {
+            Vec2::ZERO
+        } else if let Some(player_action_state) = player_action_state {
+            top_down_direction(player_action_state)
+        } else {
+            global_direction
+        };
+        controller.accelerate(direction, time.delta_seconds());
+        if controller.drive == TopDownDrive::Transform {
+            transform.translation += controller.velocity.extend(0.0) * time.delta_seconds();
         }
     }
 }
@@ -116,7 +222,177 @@ pub struct SimpleTopDownControllerPlugin;
 
 impl Plugin for SimpleTopDownControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(TopDownControllerPlugin)
-            .add_systems(Update, simple_top_down_controller);
+        app.add_plugins(TopDownControllerPlugin).add_systems(
+            Update,
+            simple_top_down_controller.after(input::InputMappingSystem),
+        );
+        #[cfg(feature = "bevy_rapier2d")]
+        app.add_systems(
+            Update,
+            (
+                crate::physics2d::read_top_down_velocity
+                    .after(input::InputMappingSystem)
+                    .before(simple_top_down_controller),
+                crate::physics2d::top_down_velocity_controller.after(simple_top_down_controller),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{InputRecording, InputReplay, RecordedFrame};
+    use std::time::Duration;
+
+    fn replay_app(controller: SimpleTopDownController, frames: Vec<Vec<TopDownAction>>) -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(bevy::input::InputPlugin)
+            .add_plugins(SimpleTopDownControllerPlugin)
+            .insert_resource(InputReplay::new(InputRecording {
+                frames: frames
+                    .into_iter()
+                    .enumerate()
+                    .map(|(frame, actions)| RecordedFrame {
+                        frame: frame as u32,
+                        actions: actions.into_iter().map(|action| (action, 1.0)).collect(),
+                        sliders: vec![],
+                    })
+                    .collect(),
+            }));
+        app.world.spawn((controller, Transform::default()));
+        app
+    }
+
+    fn step(app: &mut App, seconds: f32) -> Vec2 {
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+        app.world
+            .query::<&Transform>()
+            .single(&app.world)
+            .translation
+            .truncate()
+    }
+
+    fn assert_vec2(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 0.001, "{a} != {b}");
+    }
+
+    #[test]
+    fn simple_top_down_controller_replay() {
+        let mut app = replay_app(
+            SimpleTopDownController::new(2.0),
+            vec![
+                vec![TopDownAction::MoveRight, TopDownAction::MoveUp],
+                vec![TopDownAction::MoveLeft],
+            ],
+        );
+        let diagonal = 1.0 / 2.0_f32.sqrt();
+        assert_vec2(step(&mut app, 0.5), Vec2::new(diagonal, diagonal));
+        assert_vec2(step(&mut app, 0.25), Vec2::new(diagonal - 0.5, diagonal));
+        assert_vec2(step(&mut app, 0.5), Vec2::new(diagonal - 0.5, diagonal));
+    }
+
+    #[test]
+    fn simple_top_down_controller_acceleration() {
+        let mut app = replay_app(
+            SimpleTopDownController::new(4.0).with_acceleration(4.0, 8.0),
+            vec![
+                vec![TopDownAction::MoveRight],
+                vec![TopDownAction::MoveRight],
+            ],
+        );
+        assert_vec2(step(&mut app, 0.5), Vec2::new(1.0, 0.0));
+        assert_vec2(step(&mut app, 0.5), Vec2::new(3.0, 0.0));
+        assert_vec2(step(&mut app, 0.25), Vec2::new(3.5, 0.0));
+        assert_vec2(step(&mut app, 0.25), Vec2::new(3.5, 0.0));
+    }
+
+    #[test]
+    fn simple_top_down_controller_player_action_state() {
+        let mut app = replay_app(
+            SimpleTopDownController::new(2.0),
+            vec![vec![TopDownAction::MoveRight]],
+        );
+        let mut player_action_state = input::ActionState::default();
+        player_action_state.update(
+            &[(TopDownAction::MoveUp, 1.0)].into_iter().collect(),
+            Duration::ZERO,
+        );
+        let player = app
+            .world
+            .spawn((
+                SimpleTopDownController::new(2.0),
+                Transform::default(),
+                player_action_state,
+            ))
+            .id();
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(0.5));
+        app.update();
+        let global = app
+            .world
+            .query_filtered::<&Transform, Without<input::ActionState<TopDownAction>>>()
+            .single(&app.world)
+            .translation;
+        assert_vec2(global.truncate(), Vec2::new(1.0, 0.0));
+        let player = app.world.get::<Transform>(player).unwrap().translation;
+        assert_vec2(player.truncate(), Vec2::new(0.0, 1.0));
+    }
+
+    #[cfg(feature = "bevy_rapier2d")]
+    #[test]
+    fn simple_top_down_controller_velocity_drive() {
+        use bevy_rapier2d::prelude::Velocity;
+
+        let mut app = replay_app(
+            SimpleTopDownController::new(2.0).with_physics(),
+            vec![vec![TopDownAction::MoveRight]],
+        );
+        let entity = app
+            .world
+            .query_filtered::<Entity, With<SimpleTopDownController>>()
+            .single(&app.world);
+        app.world.entity_mut(entity).insert(Velocity::zero());
+        assert_vec2(step(&mut app, 0.5), Vec2::ZERO);
+        assert_vec2(
+            app.world.get::<Velocity>(entity).unwrap().linvel,
+            Vec2::new(2.0, 0.0),
+        );
+    }
+
+    #[cfg(feature = "bevy_rapier2d")]
+    #[test]
+    fn simple_top_down_controller_velocity_drive_keeps_collisions() {
+        use bevy_rapier2d::prelude::Velocity;
+
+        let mut app = replay_app(
+            SimpleTopDownController::new(4.0)
+                .with_acceleration(4.0, 4.0)
+                .with_physics(),
+            vec![vec![TopDownAction::MoveRight]; 2],
+        );
+        let entity = app
+            .world
+            .query_filtered::<Entity, With<SimpleTopDownController>>()
+            .single(&app.world);
+        app.world.entity_mut(entity).insert(Velocity::zero());
+        step(&mut app, 0.5);
+        assert_vec2(
+            app.world.get::<Velocity>(entity).unwrap().linvel,
+            Vec2::new(2.0, 0.0),
+        );
+
+        app.world.get_mut::<Velocity>(entity).unwrap().linvel = Vec2::ZERO;
+        step(&mut app, 0.25);
+        assert_vec2(
+            app.world.get::<Velocity>(entity).unwrap().linvel,
+            Vec2::new(1.0, 0.0),
+        );
     }
 }
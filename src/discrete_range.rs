@@ -0,0 +1,574 @@
+//! Provides a range type with integer values as component.
+//!
+//! This is the discrete version of the `range` module.  It can be used for
+//! things like ammo, lives or inventory stacks where the value must be exact.
+//!
+//! The change_per_second attribute is accumulated over the frames and the value
+//! is only changed in whole steps, so the current value never drifts.
+//!
+//! When the range reaches a limit, DiscreteStartRangeLimitReachedEvent,
+//! DiscreteEndRangeLimitReachedEvent and DiscreteLimitLeftEvent are sent.  They are
+//! separate from the events of `Range`, so a `Range` and a `DiscreteRange` can share
+//! the same marker type.  Changes of the value are reported with a
+//! DiscreteRangeChangedEvent.  Changes done with the DiscreteRangeWriter system param
+//! are reported immediately.
+//!
+//! # Examples
+//! ```rust
+//! use bevy::prelude::*;
+//! use some_bevy_tools::discrete_range::{DiscreteRange, DiscreteRangePlugin};
+//!
+//! #[derive(Default, TypePath)]
+//! struct Ammo;
+//!
+//! App::new()
+//!     .add_plugins(DiscreteRangePlugin::<Ammo, u32>::default());
+//! ```
+use bevy::{ecs::system::SystemParam, prelude::*, reflect::GetTypeRegistration};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::range::{
+    EndRangeLimitReachedEvent, LimitLeftEvent, RangeChangeSource, RangeLimit,
+    RangeLimitEventWriters, ReportedRange, StartRangeLimitReachedEvent,
+};
+
+/// Integer types which can be used as value of a DiscreteRange.
+pub trait DiscreteValue:
+    Copy + Ord + std::fmt::Debug + FromReflect + TypePath + Send + Sync + 'static
+{
+    /// Add the signed delta to the value and saturate at the bounds of the type.
+    fn offset(self, delta: i64) -> Self;
+
+    /// The difference between the value and the other value.
+    fn difference(self, other: Self) -> i64;
+}
+
+macro_rules! impl_discrete_value {
+    ($($value_type:ty),*) => {
+        $(
+            impl DiscreteValue for $value_type {
+                fn offset(self, delta: i64) -> Self {
+                    (self as i128 + delta as i128)
+                        .clamp(<$value_type>::MIN as i128, <$value_type>::MAX as i128)
+                        as $value_type
+                }
+
+                fn difference(self, other: Self) -> i64 {
+                    (self as i128 - other as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+                }
+            }
+        )*
+    };
+}
+impl_discrete_value!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+/// The result of modifying a discrete range.  It is Ok if the value is within the range,
+/// StartLimitReached if the value is below the range, and EndLimitReached if the
+/// value is above the range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModifyDiscreteRangeResult<V> {
+    Ok,
+    StartLimitReached { low_limit: V, value: V },
+    EndLimitReached { high_limit: V, value: V },
+}
+
+/// A range that contains an integer between two values.
+///
+/// It works like `range::Range` but the value is always exact.  The type
+/// parameter V defines the integer type which is i32 by default.
+///
+/// # Examples
+/// ```rust
+/// use some_bevy_tools::discrete_range::DiscreteRange;
+///
+/// #[derive(Default)]
+/// struct Ammo;
+///
+/// let mut range = DiscreteRange::<Ammo, u32>::default()
+///     .with_start(0)
+///     .with_end(30)
+///     .with_current(12);
+/// assert_eq!(range.get(), 12);
+/// range.modify(-20);
+/// assert_eq!(range.get(), 0);
+/// ```
+#[derive(Clone, Debug, Component, PartialEq, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(
+        bound(
+            serialize = "V: Serialize",
+            deserialize = "V: Deserialize<'de> + Default"
+        ),
+        default
+    )
+)]
+pub struct DiscreteRange<T, V: DiscreteValue = i32> {
+    start: V,
+    end: V,
+    current: V,
+    change_per_second: f32,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    accumulated: f32,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    reported: Option<V>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    limit: Option<RangeLimit>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, V: DiscreteValue> DiscreteRange<T, V> {
+    /// Create a new range.
+    /// The current value is set to the end value.
+    /// The change_per_second is set to 0 and so the value will not be modified automatically.
+    pub fn new(start: V, end: V) -> DiscreteRange<T, V> {
+        DiscreteRange {
+            start,
+            end,
+            current: end,
+            change_per_second: 0.0,
+            accumulated: 0.0,
+            reported: Some(end),
+            limit: Some(RangeLimit::End),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Create a new range with the start value set to the start value.
+    pub fn with_start(self, start: V) -> DiscreteRange<T, V> {
+        DiscreteRange { start, ..self }.mark_reported()
+    }
+    /// Create a new range with the end value set to the end value.
+    pub fn with_end(self, end: V) -> DiscreteRange<T, V> {
+        DiscreteRange { end, ..self }.mark_reported()
+    }
+    /// Create a new range with the current value set to the current value.
+    pub fn with_current(self, current: V) -> DiscreteRange<T, V> {
+        DiscreteRange { current, ..self }.mark_reported()
+    }
+    /// Create a new range with the change_per_second value set to the change_per_second value.
+    pub fn with_change_per_second(self, change_per_second: f32) -> DiscreteRange<T, V> {
+        DiscreteRange {
+            change_per_second,
+            ..self
+        }
+    }
+
+    /// Get the start value.
+    pub fn get_start(&self) -> V {
+        self.start
+    }
+    /// Get the end value.
+    pub fn get_end(&self) -> V {
+        self.end
+    }
+    /// Set the change_per_second value.
+    pub fn set_change_per_second(&mut self, change_per_second: f32) {
+        self.change_per_second = change_per_second
+    }
+    /// Get the change_per_second value.
+    pub fn get_change_per_second(&self) -> f32 {
+        self.change_per_second
+    }
+    /// Get the limit the range is currently at.
+    ///
+    /// This is the limit for which the last limit event was sent and it is None
+    /// if the value is between the limits.
+    pub fn get_limit(&self) -> Option<RangeLimit> {
+        self.limit
+    }
+
+    /// Set the current value.
+    /// The result is Ok if the value is within the range,
+    /// StartLimitReached if the value is equal or below the range, and
+    /// EndLimitReached if the value is equal or above the range.
+    /// If a value is outside the range, the current value is set to the
+    /// closest limit.
+    pub fn set(&mut self, value: V) -> ModifyDiscreteRangeResult<V> {
+        if value <= self.start {
+            self.current = self.start;
+            ModifyDiscreteRangeResult::StartLimitReached {
+                low_limit: self.start,
+                value,
+            }
+        } else if value >= self.end {
+            self.current = self.end;
+            ModifyDiscreteRangeResult::EndLimitReached {
+                high_limit: self.end,
+                value,
+            }
+        } else {
+            self.current = value;
+            ModifyDiscreteRangeResult::Ok
+        }
+    }
+
+    /// Get the current value.
+    pub fn get(&self) -> V {
+        self.current
+    }
+
+    /// Modify the current value by the signed delta.
+    /// The result is the same as for `set`.
+    pub fn modify(&mut self, delta: i64) -> ModifyDiscreteRangeResult<V> {
+        self.set(self.current.offset(delta))
+    }
+
+    /// The limit at which the current value is, without looking at the last report.
+    fn detect_limit(&self) -> Option<RangeLimit> {
+        if self.current <= self.start {
+            Some(RangeLimit::Start)
+        } else if self.current >= self.end {
+            Some(RangeLimit::End)
+        } else {
+            None
+        }
+    }
+
+    /// Treat the current state as already reported so no events are sent for it.
+    fn mark_reported(mut self) -> DiscreteRange<T, V> {
+        self.reset_reported();
+        self
+    }
+
+    /// Treat the current state as already reported so no events are sent for it.
+    fn reset_reported(&mut self) {
+        self.reported = Some(self.current);
+        self.limit = self.detect_limit();
+    }
+}
+
+impl<T: Send + Sync + 'static, V: DiscreteValue> ReportedRange for DiscreteRange<T, V> {
+    fn reset_reported(&mut self) {
+        Self::reset_reported(self);
+    }
+}
+
+impl<T, V: DiscreteValue + Default> Default for DiscreteRange<T, V> {
+    fn default() -> Self {
+        DiscreteRange::new(V::default(), V::default().offset(1))
+    }
+}
+
+/// An event which is sent whenever the value of a discrete range changes.
+#[derive(Debug, Event)]
+pub struct DiscreteRangeChangedEvent<T, V = i32> {
+    pub entity: Entity,
+    pub previous: V,
+    pub current: V,
+    pub delta: i64,
+    pub source: RangeChangeSource,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+/// Marker which separates the limit events of discrete ranges from the ones of `Range`.
+#[derive(Debug)]
+pub struct Discrete<T, V = i32> {
+    _phantom: std::marker::PhantomData<(T, V)>,
+}
+
+/// An event which is sent when a discrete range hits a start limit.
+///
+/// For example for ammo it could mean that the weapon must be reloaded.
+pub type DiscreteStartRangeLimitReachedEvent<T, V = i32> =
+    StartRangeLimitReachedEvent<Discrete<T, V>>;
+
+/// An event which is sent when a discrete range hits an end limit.
+pub type DiscreteEndRangeLimitReachedEvent<T, V = i32> = EndRangeLimitReachedEvent<Discrete<T, V>>;
+
+/// An event which is sent when a discrete range leaves a limit it was at before.
+pub type DiscreteLimitLeftEvent<T, V = i32> = LimitLeftEvent<Discrete<T, V>>;
+
+/// All event writers which are required to report changes of a discrete range.
+#[derive(SystemParam)]
+pub struct DiscreteRangeEventWriters<'w, T: Send + Sync + 'static, V: DiscreteValue> {
+    range_changed_event_writer: EventWriter<'w, DiscreteRangeChangedEvent<T, V>>,
+    limit_event_writers: RangeLimitEventWriters<'w, Discrete<T, V>>,
+}
+
+impl<'w, T: Send + Sync + 'static, V: DiscreteValue> DiscreteRangeEventWriters<'w, T, V> {
+    /// Send the events for everything which changed on the range since the last report.
+    ///
+    /// Limit events are only sent when a limit is entered or left.
+    pub fn report(
+        &mut self,
+        entity: Entity,
+        range: &mut DiscreteRange<T, V>,
+        source: RangeChangeSource,
+    ) {
+        let current = range.current;
+        if let Some(previous) = range.reported.filter(|previous| *previous != current) {
+            self.range_changed_event_writer
+                .send(DiscreteRangeChangedEvent {
+                    entity,
+                    previous,
+                    current,
+                    delta: current.difference(previous),
+                    source,
+                    _phantom: std::marker::PhantomData,
+                });
+        }
+        range.reported = Some(current);
+
+        let previous_limit = range.limit;
+        range.limit = range.detect_limit();
+        self.limit_event_writers
+            .send_transition(entity, previous_limit, range.limit);
+    }
+}
+
+/// A system param to modify discrete ranges which reports the changes immediately.
+///
+/// Changes done with this param send the DiscreteRangeChangedEvent with the
+/// `External` source and the limit events in the same frame.
+///
+/// # Examples
+/// ```rust
+/// use bevy::prelude::*;
+/// use some_bevy_tools::discrete_range::DiscreteRangeWriter;
+///
+/// struct Ammo;
+///
+/// fn shoot(mut ranges: DiscreteRangeWriter<Ammo, u32>, query: Query<Entity>) {
+///     for entity in query.iter() {
+///         ranges.modify(entity, -1);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct DiscreteRangeWriter<'w, 's, T: Send + Sync + 'static, V: DiscreteValue = i32> {
+    range_query: Query<'w, 's, &'static mut DiscreteRange<T, V>>,
+    event_writers: DiscreteRangeEventWriters<'w, T, V>,
+}
+
+impl<'w, 's, T: Send + Sync + 'static, V: DiscreteValue> DiscreteRangeWriter<'w, 's, T, V> {
+    /// Get the range of the entity.
+    pub fn get(&self, entity: Entity) -> Option<&DiscreteRange<T, V>> {
+        self.range_query.get(entity).ok()
+    }
+
+    /// Set the value of the range of the entity.
+    ///
+    /// Returns None if the entity has no range.
+    pub fn set(&mut self, entity: Entity, value: V) -> Option<ModifyDiscreteRangeResult<V>> {
+        let mut range = self.range_query.get_mut(entity).ok()?;
+        let result = range.set(value);
+        self.event_writers
+            .report(entity, &mut range, RangeChangeSource::External);
+        Some(result)
+    }
+
+    /// Modify the value of the range of the entity by the signed delta.
+    ///
+    /// Returns None if the entity has no range.
+    pub fn modify(&mut self, entity: Entity, delta: i64) -> Option<ModifyDiscreteRangeResult<V>> {
+        let current = self.range_query.get(entity).ok()?.current;
+        self.set(entity, current.offset(delta))
+    }
+}
+
+/// A system to update the discrete range values based on their change_per_second attribute.
+///
+/// The change_per_second is accumulated and the value is only changed in whole steps.
+/// Changes which were done directly on the DiscreteRange component since the last run are
+/// reported with the `External` source.
+pub fn update_discrete_range<T: Send + Sync + 'static, V: DiscreteValue>(
+    mut range_query: Query<(Entity, &mut DiscreteRange<T, V>)>,
+    time: Res<Time>,
+    mut event_writers: DiscreteRangeEventWriters<T, V>,
+) {
+    for (entity, mut range) in range_query.iter_mut() {
+        event_writers.report(entity, &mut range, RangeChangeSource::External);
+
+        if range.change_per_second == 0.0 {
+            range.accumulated = 0.0;
+            continue;
+        }
+        range.accumulated += range.change_per_second * time.delta_seconds();
+        let steps = range.accumulated.trunc();
+        if steps != 0.0 {
+            range.accumulated -= steps;
+            if range.modify(steps as i64) != ModifyDiscreteRangeResult::Ok {
+                range.accumulated = 0.0;
+            }
+            event_writers.report(entity, &mut range, RangeChangeSource::Regeneration);
+        }
+    }
+}
+
+/// Add support for `DiscreteRange`s in your game.
+///
+/// It will produce DiscreteStartRangeLimitReachedEvent, DiscreteEndRangeLimitReachedEvent
+/// and DiscreteLimitLeftEvent when the range reaches or leaves a limit and
+/// DiscreteRangeChangedEvent when the value changes.  It will also update the
+/// DiscreteRange component based on the change_per_second attribute.
+#[derive(Debug)]
+pub struct DiscreteRangePlugin<T, V = i32> {
+    _phantom: std::marker::PhantomData<(T, V)>,
+}
+impl<T, V> Default for DiscreteRangePlugin<T, V> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+impl<T: TypePath + Send + Sync, V: DiscreteValue + GetTypeRegistration> Plugin
+    for DiscreteRangePlugin<T, V>
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<DiscreteRange<T, V>>()
+            .add_event::<DiscreteRangeChangedEvent<T, V>>()
+            .add_event::<DiscreteStartRangeLimitReachedEvent<T, V>>()
+            .add_event::<DiscreteEndRangeLimitReachedEvent<T, V>>()
+            .add_event::<DiscreteLimitLeftEvent<T, V>>()
+            .add_systems(
+                Update,
+                (
+                    crate::range::initialize_added_ranges::<DiscreteRange<T, V>>,
+                    update_discrete_range::<T, V>,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq, TypePath)]
+    struct Ammo;
+    type AmmoRange = DiscreteRange<Ammo, u32>;
+
+    #[test]
+    fn test_discrete_range_set_and_modify() {
+        let mut range = AmmoRange::new(0, 30);
+        assert_eq!(range.get(), 30);
+        assert_eq!(range.modify(-10), ModifyDiscreteRangeResult::Ok);
+        assert_eq!(range.get(), 20);
+        assert_eq!(
+            range.modify(-25),
+            ModifyDiscreteRangeResult::StartLimitReached {
+                low_limit: 0,
+                value: 0
+            }
+        );
+        assert_eq!(range.get(), 0);
+        assert_eq!(
+            range.set(31),
+            ModifyDiscreteRangeResult::EndLimitReached {
+                high_limit: 30,
+                value: 31
+            }
+        );
+        assert_eq!(range.get(), 30);
+    }
+
+    #[test]
+    fn test_discrete_range_regeneration_has_no_drift() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(DiscreteRangePlugin::<Ammo, u32>::default());
+        let entity = app
+            .world
+            .spawn(
+                AmmoRange::new(0, 10)
+                    .with_current(5)
+                    .with_change_per_second(-3.0),
+            )
+            .id();
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(0.25));
+        app.update();
+        assert_eq!(app.world.get::<AmmoRange>(entity).unwrap().get(), 5);
+        app.update();
+        assert_eq!(app.world.get::<AmmoRange>(entity).unwrap().get(), 4);
+
+        let deltas: Vec<i64> = app
+            .world
+            .resource_mut::<Events<DiscreteRangeChangedEvent<Ammo, u32>>>()
+            .drain()
+            .map(|event| event.delta)
+            .collect();
+        assert_eq!(deltas, vec![-1]);
+
+        for _ in 0..5 {
+            app.update();
+        }
+        let range = app.world.get::<AmmoRange>(entity).unwrap();
+        assert_eq!(range.get(), 0);
+        assert_eq!(range.get_limit(), Some(RangeLimit::Start));
+        assert_eq!(
+            app.world
+                .resource::<Events<DiscreteStartRangeLimitReachedEvent<Ammo, u32>>>()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_discrete_range_writer_events_are_separate_from_range() {
+        use crate::range::{Range, RangePlugin, StartRangeLimitReachedEvent};
+
+        fn shoot(
+            mut ranges: DiscreteRangeWriter<Ammo, u32>,
+            query: Query<Entity, With<AmmoRange>>,
+        ) {
+            for entity in query.iter() {
+                ranges.modify(entity, -5);
+            }
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(RangePlugin::<Ammo>::default())
+            .add_plugins(DiscreteRangePlugin::<Ammo, u32>::default());
+        let entity = app
+            .world
+            .spawn((AmmoRange::new(0, 3), Range::<Ammo>::new(0.0, 3.0)))
+            .id();
+        app.add_systems(Update, shoot.before(update_discrete_range::<Ammo, u32>));
+        app.update();
+
+        assert_eq!(app.world.get::<AmmoRange>(entity).unwrap().get(), 0);
+        let deltas: Vec<i64> = app
+            .world
+            .resource_mut::<Events<DiscreteRangeChangedEvent<Ammo, u32>>>()
+            .drain()
+            .map(|event| event.delta)
+            .collect();
+        assert_eq!(deltas, vec![-3]);
+        assert_eq!(
+            app.world
+                .resource::<Events<DiscreteStartRangeLimitReachedEvent<Ammo, u32>>>()
+                .len(),
+            1
+        );
+        assert_eq!(
+            app.world
+                .resource::<Events<StartRangeLimitReachedEvent<Ammo>>>()
+                .len(),
+            0
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_discrete_range_deserialize_with_defaults() {
+        let range: AmmoRange = ron::from_str("(end: 30, current: 12)").unwrap();
+        assert_eq!(range.get_start(), 0);
+        assert_eq!(range.get_end(), 30);
+        assert_eq!(range.get(), 12);
+        assert_eq!(range.get_change_per_second(), 0.0);
+    }
+}
@@ -1,21 +1,816 @@
 //! Add health support for components.
 //!
-//! This is basically just a range which has type aliases.
+//! This is basically just a range which has type aliases.  The HealthPlugin registers
+//! the Health component for reflection, so it can be used in scenes.
+//!
+//! Damage can be applied by sending a DamageEvent.  The damage is reduced by the
+//! Resistances of the target and ignored while the target is Invulnerable.  The
+//! effective damage is reported with a DamageAppliedEvent and it is subtracted from
+//! the Health, which sends a DeathEvent when it reaches zero.
+//!
+//! Shield and Armor are ranges which absorb damage before it reaches the Health.
+//! The DamageLayers component defines in which order they are drained and how much
+//! of the damage bleeds through each of them.  Other ranges, like a mana shield, can
+//! be used as layers after their marker was registered with the DamageLayerPlugin.
+//!
+//! What happens on death is defined by the OnDeath component.  The entity can be
+//! despawned immediately or after a delay, respawned at a stored transform with full
+//! health or it just gets a Dead marker.
+//!
+//! ## Example
+//! ```rust
+//! use bevy::prelude::*;
+//! use some_bevy_tools::health::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     commands.spawn((
+//!         Health::new(0.0, 100.0),
+//!         Resistances::default().with_resistance(DamageKind::Fire, 0.5),
+//!         HitInvulnerability::new(0.5),
+//!         OnDeath::Respawn(Transform::from_xyz(0.0, 0.0, 0.0)),
+//!     ));
+//! }
+//!
+//! fn attack(mut damage_events: EventWriter<DamageEvent>, query: Query<Entity, With<Health>>) {
+//!     for entity in query.iter() {
+//!         damage_events.send(DamageEvent::new(entity, 10.0).with_kind(DamageKind::Fire));
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins(HealthPlugin)
+//!     .add_systems(Startup, setup)
+//!     .add_systems(Update, attack);
+//! ```
 
-use crate::range;
-use bevy::prelude::*;
+use crate::{despawn, range};
+use bevy::{ecs::system::SystemState, prelude::*, utils::HashMap, utils::HashSet};
+use std::any::TypeId;
 
-#[derive(Default)]
+#[derive(Default, Reflect)]
 pub struct HealthMarker;
 
 pub type Health = range::Range<HealthMarker>;
 pub type DeathEvent = range::StartRangeLimitReachedEvent<HealthMarker>;
 pub type FullHealEvent = range::EndRangeLimitReachedEvent<HealthMarker>;
 
+#[derive(Default, Reflect)]
+pub struct ShieldMarker;
+
+pub type Shield = range::Range<ShieldMarker>;
+pub type ShieldBrokenEvent = range::StartRangeLimitReachedEvent<ShieldMarker>;
+
+#[derive(Default, Reflect)]
+pub struct ArmorMarker;
+
+pub type Armor = range::Range<ArmorMarker>;
+pub type ArmorBrokenEvent = range::StartRangeLimitReachedEvent<ArmorMarker>;
+
+/// The kind of damage which can be resisted differently.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum DamageKind {
+    #[default]
+    Physical,
+    Fire,
+    Ice,
+    Poison,
+    Custom(&'static str),
+}
+
+/// Request to apply damage to the Health of the target.
+///
+/// Negative and NaN amounts are applied as zero damage, so damage never heals.
+#[derive(Debug, Clone, Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub source: Option<Entity>,
+}
+
+impl DamageEvent {
+    /// Create a physical damage event without source.
+    pub fn new(target: Entity, amount: f32) -> Self {
+        Self {
+            target,
+            amount,
+            kind: DamageKind::default(),
+            source: None,
+        }
+    }
+
+    /// Create a new damage event with the given kind.
+    pub fn with_kind(self, kind: DamageKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// Create a new damage event with the entity which caused the damage.
+    pub fn with_source(self, source: Entity) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+}
+
+/// Sent after a DamageEvent was applied to the Health of the target.
+///
+/// The amount is the damage after the resistances were applied and absorbed is
+/// the part of it which was taken by the DamageLayers.
+#[derive(Debug, Clone, Event)]
+pub struct DamageAppliedEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub absorbed: f32,
+    pub original_amount: f32,
+    pub kind: DamageKind,
+    pub source: Option<Entity>,
+}
+
+/// Reduces the damage of specific kinds.
+///
+/// A resistance of 0.25 reduces the damage by 25%, 1.0 makes the entity immune
+/// and negative values increase the damage.  Resistances above 1.0 also make the
+/// entity immune, damage never heals.
+#[derive(Debug, Clone, Default, Component)]
+pub struct Resistances {
+    resistances: HashMap<DamageKind, f32>,
+}
+
+impl Resistances {
+    /// Create new resistances with the resistance for the damage kind set.
+    pub fn with_resistance(mut self, kind: DamageKind, resistance: f32) -> Self {
+        self.set(kind, resistance);
+        self
+    }
+
+    /// Set the resistance for the damage kind.
+    pub fn set(&mut self, kind: DamageKind, resistance: f32) {
+        self.resistances.insert(kind, resistance);
+    }
+
+    /// Get the resistance for the damage kind which is 0 if it is not set.
+    pub fn get(&self, kind: DamageKind) -> f32 {
+        self.resistances.get(&kind).copied().unwrap_or(0.0)
+    }
+
+    /// Apply the resistance on the damage amount.
+    ///
+    /// The result is never negative.
+    pub fn apply(&self, kind: DamageKind, amount: f32) -> f32 {
+        (amount * (1.0 - self.get(kind))).max(0.0)
+    }
+}
+
+/// A range which can absorb damage before it reaches the Health, identified by the
+/// marker type of the range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DamagePool {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl DamagePool {
+    /// The pool of `Range<T>`.  The marker must be registered with the DamageLayerPlugin.
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+/// A pool in the DamageLayers.
+///
+/// The bleed through is the fraction of the damage which passes the layer even if
+/// it could absorb it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DamageLayer {
+    pub pool: DamagePool,
+    pub bleed_through: f32,
+}
+
+impl DamageLayer {
+    pub fn new(pool: DamagePool) -> Self {
+        Self {
+            pool,
+            bleed_through: 0.0,
+        }
+    }
+
+    /// Create a new layer with the bleed through set.
+    pub fn with_bleed_through(self, bleed_through: f32) -> Self {
+        Self {
+            bleed_through,
+            ..self
+        }
+    }
+}
+
+/// The layers which absorb damage before it reaches the Health, in the order they
+/// are drained.
+///
+/// Layers whose range is missing on the entity or whose pool is not registered are
+/// skipped.
+#[derive(Debug, Clone, Default, Component)]
+pub struct DamageLayers {
+    pub layers: Vec<DamageLayer>,
+}
+
+impl DamageLayers {
+    /// Create new damage layers with the layer added at the end.
+    pub fn with_layer(mut self, layer: DamageLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+/// Drains the range of a damage pool.
+trait DamagePoolAbsorber: Send + Sync {
+    fn absorb(&mut self, world: &mut World, entity: Entity, amount: f32, bleed_through: f32)
+        -> f32;
+}
+
+/// Absorbs damage with the RangeWriter of `Range<T>`, so the changes are reported
+/// immediately.
+struct RangeDamageAbsorber<T: Send + Sync + 'static> {
+    state: Option<SystemState<range::RangeWriter<'static, 'static, T>>>,
+}
+
+impl<T: Send + Sync + 'static> DamagePoolAbsorber for RangeDamageAbsorber<T> {
+    fn absorb(
+        &mut self,
+        world: &mut World,
+        entity: Entity,
+        amount: f32,
+        bleed_through: f32,
+    ) -> f32 {
+        let state = self.state.get_or_insert_with(|| SystemState::new(world));
+        let remaining = absorb_damage(&mut state.get_mut(world), entity, amount, bleed_through);
+        state.apply(world);
+        remaining
+    }
+}
+
+/// The damage pools which were registered with the DamageLayerPlugin.
+#[derive(Default, Resource)]
+pub struct DamagePools {
+    absorbers: HashMap<DamagePool, Box<dyn DamagePoolAbsorber>>,
+}
+
+impl DamagePools {
+    /// Register `Range<T>` as damage pool.
+    pub fn register<T: Send + Sync + 'static>(&mut self) {
+        self.absorbers.insert(
+            DamagePool::of::<T>(),
+            Box::new(RangeDamageAbsorber::<T> { state: None }),
+        );
+    }
+
+    /// Returns true if the pool was registered.
+    pub fn contains(&self, pool: DamagePool) -> bool {
+        self.absorbers.contains_key(&pool)
+    }
+
+    /// Let the pool of the entity absorb the damage and return the remaining damage.
+    pub fn absorb(
+        &mut self,
+        world: &mut World,
+        entity: Entity,
+        layer: &DamageLayer,
+        amount: f32,
+    ) -> f32 {
+        match self.absorbers.get_mut(&layer.pool) {
+            Some(absorber) => absorber.absorb(world, entity, amount, layer.bleed_through),
+            None => amount,
+        }
+    }
+}
+
+/// Drain the range by the damage which is not bleeding through and return the
+/// remaining damage.
+fn absorb_damage<T: Send + Sync + 'static>(
+    range_writer: &mut range::RangeWriter<T>,
+    entity: Entity,
+    amount: f32,
+    bleed_through: f32,
+) -> f32 {
+    let Some(range) = range_writer.get(entity) else {
+        return amount;
+    };
+    let available = (range.get_raw() - range.get_start()).max(0.0);
+    let absorbed = (amount * (1.0 - bleed_through)).clamp(0.0, available);
+    if absorbed > 0.0 {
+        range_writer.modify(entity, -absorbed);
+    }
+    amount - absorbed
+}
+
+/// While an entity has this component, all damage is ignored.
+///
+/// Without timer, the entity stays invulnerable until the component is removed.
+#[derive(Debug, Clone, Default, Component)]
+pub struct Invulnerable {
+    pub timer: Option<Timer>,
+}
+
+impl Invulnerable {
+    /// Invulnerability which is removed after the duration in seconds.
+    pub fn with_duration(duration: f32) -> Self {
+        Self {
+            timer: Some(Timer::from_seconds(duration, TimerMode::Once)),
+        }
+    }
+}
+
+/// Makes an entity Invulnerable for the duration in seconds after it took damage.
+#[derive(Debug, Clone, Component)]
+pub struct HitInvulnerability {
+    pub duration: f32,
+}
+
+impl HitInvulnerability {
+    pub fn new(duration: f32) -> Self {
+        Self { duration }
+    }
+}
+
+/// Defines what happens to an entity when its Health reaches zero.
+#[derive(Debug, Clone, Component)]
+pub enum OnDeath {
+    /// Despawn the entity and its children.
+    Despawn,
+
+    /// Insert the Dead marker and despawn the entity after the duration in seconds.
+    ///
+    /// This requires the `despawn::AutoDespawnPlugin`.
+    DespawnAfter(f32),
+
+    /// Move the entity to the transform and restore its Health.
+    Respawn(Transform),
+
+    /// Only insert the Dead marker.
+    MarkDead,
+}
+
+/// Marker for entities which died and are not despawned yet.
+#[derive(Debug, Default, Component)]
+pub struct Dead;
+
+/// Sent after an entity was respawned by its OnDeath policy.
+#[derive(Debug, Clone, Event)]
+pub struct RespawnedEvent {
+    pub entity: Entity,
+}
+
+/// A DamageEvent which passed the invulnerability checks.
+struct Hit {
+    event: DamageEvent,
+    amount: f32,
+    layers: Vec<DamageLayer>,
+}
+
+/// System which applies the DamageEvents on the Health of their target.
+///
+/// It is an exclusive system, so the DamageLayers can drain any registered pool.
+#[allow(clippy::type_complexity)]
+pub fn apply_damage_system(
+    world: &mut World,
+    damage_state: &mut SystemState<(
+        EventReader<DamageEvent>,
+        Query<(
+            Option<&Resistances>,
+            Option<&DamageLayers>,
+            Option<&HitInvulnerability>,
+            Has<Invulnerable>,
+            Has<Health>,
+        )>,
+    )>,
+    health_state: &mut SystemState<range::RangeWriter<'static, 'static, HealthMarker>>,
+    mut hit_entities: Local<HashSet<Entity>>,
+) {
+    hit_entities.clear();
+    let mut hits = Vec::new();
+    let mut hit_invulnerabilities = Vec::new();
+    let (mut damage_events, target_query) = damage_state.get_mut(world);
+    for damage_event in damage_events.read() {
+        let Ok((resistances, damage_layers, hit_invulnerability, invulnerable, has_health)) =
+            target_query.get(damage_event.target)
+        else {
+            continue;
+        };
+        if invulnerable || !has_health || hit_entities.contains(&damage_event.target) {
+            continue;
+        }
+        let damage = damage_event.amount.max(0.0);
+        let amount = resistances
+            .map(|resistances| resistances.apply(damage_event.kind, damage))
+            .unwrap_or(damage);
+        if let Some(hit_invulnerability) = hit_invulnerability {
+            hit_invulnerabilities.push((damage_event.target, hit_invulnerability.duration));
+            hit_entities.insert(damage_event.target);
+        }
+        hits.push(Hit {
+            event: damage_event.clone(),
+            amount,
+            layers: damage_layers
+                .map(|damage_layers| damage_layers.layers.clone())
+                .unwrap_or_default(),
+        });
+    }
+    if hits.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, mut damage_pools: Mut<DamagePools>| {
+        for hit in hits {
+            let target = hit.event.target;
+            let mut remaining = hit.amount;
+            for layer in hit.layers.iter() {
+                remaining = damage_pools
+                    .bypass_change_detection()
+                    .absorb(world, target, layer, remaining);
+            }
+            health_state.get_mut(world).modify(target, -remaining);
+            world.send_event(DamageAppliedEvent {
+                target,
+                amount: hit.amount,
+                absorbed: hit.amount - remaining,
+                original_amount: hit.event.amount,
+                kind: hit.event.kind,
+                source: hit.event.source,
+            });
+        }
+    });
+    for (entity, duration) in hit_invulnerabilities {
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.insert(Invulnerable::with_duration(duration));
+        }
+    }
+}
+
+/// System which removes the Invulnerable component when its timer finished.
+pub fn invulnerability_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        if let Some(timer) = invulnerable.timer.as_mut() {
+            if timer.tick(time.delta()).finished() {
+                commands.entity(entity).remove::<Invulnerable>();
+            }
+        }
+    }
+}
+
+/// System which handles the DeathEvents according to the OnDeath component.
+///
+/// The restored Health of respawned entities is reported in the same frame as the
+/// RespawnedEvent.
+pub fn death_system(
+    mut commands: Commands,
+    mut death_params: ParamSet<(EventReader<DeathEvent>, range::RangeWriter<HealthMarker>)>,
+    mut respawned_event_writer: EventWriter<RespawnedEvent>,
+    mut query: Query<(&OnDeath, Option<&mut Transform>)>,
+) {
+    let dead_entities: Vec<Entity> = death_params
+        .p0()
+        .read()
+        .map(|death_event| death_event.entity)
+        .collect();
+    for entity in dead_entities {
+        let Ok((on_death, transform)) = query.get_mut(entity) else {
+            continue;
+        };
+        match on_death {
+            OnDeath::Despawn => {
+                commands.entity(entity).despawn_recursive();
+            }
+            OnDeath::DespawnAfter(duration) => {
+                commands
+                    .entity(entity)
+                    .insert((Dead, despawn::AutoDespawn::with_duration(*duration)));
+            }
+            OnDeath::Respawn(respawn_transform) => {
+                if let Some(mut transform) = transform {
+                    *transform = *respawn_transform;
+                } else {
+                    commands.entity(entity).insert(*respawn_transform);
+                }
+                let mut health_writer = death_params.p1();
+                if let Some(end) = health_writer.get(entity).map(|health| health.get_end()) {
+                    health_writer.set(entity, end);
+                }
+                respawned_event_writer.send(RespawnedEvent { entity });
+            }
+            OnDeath::MarkDead => {
+                commands.entity(entity).insert(Dead);
+            }
+        }
+    }
+}
+
+/// Register `Range<T>` as pool which can be used in DamageLayers.
+///
+/// The range itself must be added with the `range::RangePlugin`.
+pub struct DamageLayerPlugin<T> {
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> Default for DamageLayerPlugin<T> {
+    fn default() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Plugin for DamageLayerPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DamagePools>()
+            .world
+            .resource_mut::<DamagePools>()
+            .register::<T>();
+    }
+}
+
+/// Add support for Health, Shield, Armor and damage in your game.
 pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(range::RangePlugin::<HealthMarker>::default());
+        app.add_plugins(range::RangePlugin::<HealthMarker>::default())
+            .add_plugins(range::RangePlugin::<ShieldMarker>::default())
+            .add_plugins(range::RangePlugin::<ArmorMarker>::default())
+            .add_plugins(DamageLayerPlugin::<ShieldMarker>::default())
+            .add_plugins(DamageLayerPlugin::<ArmorMarker>::default())
+            .add_event::<DamageEvent>()
+            .add_event::<DamageAppliedEvent>()
+            .add_event::<RespawnedEvent>()
+            .add_systems(
+                Update,
+                (invulnerability_system, apply_damage_system)
+                    .chain()
+                    .before(range::update_range::<HealthMarker>),
+            )
+            .add_systems(
+                Update,
+                death_system.after(range::update_range::<HealthMarker>),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health_test_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(HealthPlugin);
+        app
+    }
+
+    #[test]
+    fn test_damage_with_resistances_and_invulnerability() {
+        let mut app = health_test_app();
+        let entity = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0),
+                Resistances::default().with_resistance(DamageKind::Fire, 0.5),
+                HitInvulnerability::new(1.0),
+            ))
+            .id();
+
+        app.world
+            .send_event(DamageEvent::new(entity, 20.0).with_kind(DamageKind::Fire));
+        app.world.send_event(DamageEvent::new(entity, 20.0));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 90.0);
+        let applied: Vec<f32> = app
+            .world
+            .resource_mut::<Events<DamageAppliedEvent>>()
+            .drain()
+            .map(|event| event.amount)
+            .collect();
+        assert_eq!(applied, vec![10.0]);
+        assert!(app.world.get::<Invulnerable>(entity).is_some());
+
+        app.world.send_event(DamageEvent::new(entity, 20.0));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 90.0);
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(1.0));
+        app.update();
+        assert!(app.world.get::<Invulnerable>(entity).is_none());
+
+        app.world.send_event(DamageEvent::new(entity, 100.0));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 0.0);
+        assert_eq!(app.world.resource::<Events<DeathEvent>>().len(), 1);
+    }
+
+    #[test]
+    fn test_resistance_above_one_does_not_heal() {
+        let mut app = health_test_app();
+        let entity = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0).with_current(50.0),
+                Resistances::default().with_resistance(DamageKind::Poison, 1.5),
+            ))
+            .id();
+
+        app.world
+            .send_event(DamageEvent::new(entity, 20.0).with_kind(DamageKind::Poison));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 50.0);
+        let applied: Vec<f32> = app
+            .world
+            .resource_mut::<Events<DamageAppliedEvent>>()
+            .drain()
+            .map(|event| event.amount)
+            .collect();
+        assert_eq!(applied, vec![0.0]);
+    }
+
+    #[test]
+    fn test_negative_damage_does_not_heal() {
+        let mut app = health_test_app();
+        let entity = app
+            .world
+            .spawn(Health::new(0.0, 100.0).with_current(50.0))
+            .id();
+
+        app.world.send_event(DamageEvent::new(entity, -20.0));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 50.0);
+        app.world.send_event(DamageEvent::new(entity, f32::NAN));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 50.0);
+        let applied: Vec<f32> = app
+            .world
+            .resource_mut::<Events<DamageAppliedEvent>>()
+            .drain()
+            .map(|event| event.amount)
+            .collect();
+        assert_eq!(applied, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_death_policies() {
+        let mut app = health_test_app();
+        let respawn_transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let respawning = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0),
+                Transform::default(),
+                OnDeath::Respawn(respawn_transform),
+            ))
+            .id();
+        let marked = app
+            .world
+            .spawn((Health::new(0.0, 100.0), OnDeath::MarkDead))
+            .id();
+        let despawned = app
+            .world
+            .spawn((Health::new(0.0, 100.0), OnDeath::Despawn))
+            .id();
+
+        for entity in [respawning, marked, despawned] {
+            app.world.send_event(DamageEvent::new(entity, 100.0));
+        }
+        app.update();
+
+        assert_eq!(app.world.get::<Health>(respawning).unwrap().get(), 100.0);
+        assert_eq!(
+            *app.world.get::<Transform>(respawning).unwrap(),
+            respawn_transform
+        );
+        let respawned: Vec<Entity> = app
+            .world
+            .resource_mut::<Events<RespawnedEvent>>()
+            .drain()
+            .map(|event| event.entity)
+            .collect();
+        assert_eq!(respawned, vec![respawning]);
+        let full_heals: Vec<Entity> = app
+            .world
+            .resource_mut::<Events<FullHealEvent>>()
+            .drain()
+            .map(|event| event.entity)
+            .collect();
+        assert_eq!(full_heals, vec![respawning]);
+        assert!(app.world.get::<Dead>(marked).is_some());
+        assert!(app.world.get_entity(despawned).is_none());
+    }
+
+    #[test]
+    fn test_despawn_after_death() {
+        let mut app = health_test_app();
+        app.add_plugins(despawn::AutoDespawnPlugin);
+        let entity = app
+            .world
+            .spawn((Health::new(0.0, 100.0), OnDeath::DespawnAfter(1.0)))
+            .id();
+
+        app.world.send_event(DamageEvent::new(entity, 100.0));
+        app.update();
+        assert!(app.world.get::<Dead>(entity).is_some());
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(1.0));
+        app.update();
+        assert!(app.world.get_entity(entity).is_none());
+    }
+
+    #[test]
+    fn test_damage_layers() {
+        let mut app = health_test_app();
+        let entity = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0),
+                Shield::new(0.0, 20.0),
+                Armor::new(0.0, 50.0),
+                DamageLayers::default()
+                    .with_layer(DamageLayer::new(DamagePool::of::<ShieldMarker>()))
+                    .with_layer(
+                        DamageLayer::new(DamagePool::of::<ArmorMarker>()).with_bleed_through(0.5),
+                    ),
+            ))
+            .id();
+
+        app.world.send_event(DamageEvent::new(entity, 40.0));
+        app.update();
+        assert_eq!(app.world.get::<Shield>(entity).unwrap().get(), 0.0);
+        assert_eq!(app.world.get::<Armor>(entity).unwrap().get(), 40.0);
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 90.0);
+        assert_eq!(app.world.resource::<Events<ShieldBrokenEvent>>().len(), 1);
+        let absorbed: Vec<f32> = app
+            .world
+            .resource_mut::<Events<DamageAppliedEvent>>()
+            .drain()
+            .map(|event| event.absorbed)
+            .collect();
+        assert_eq!(absorbed, vec![30.0]);
+    }
+
+    #[test]
+    fn test_damage_layers_with_fractional_pool() {
+        let mut app = health_test_app();
+        let entity = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0),
+                Shield::new(0.0, 20.0).with_current(0.6),
+                DamageLayers::default()
+                    .with_layer(DamageLayer::new(DamagePool::of::<ShieldMarker>())),
+            ))
+            .id();
+
+        app.world.send_event(DamageEvent::new(entity, 1.0));
+        app.update();
+        assert_eq!(app.world.get::<Shield>(entity).unwrap().get_raw(), 0.0);
+        let health = app.world.get::<Health>(entity).unwrap().get_raw();
+        assert!((health - 99.6).abs() < 1e-4, "health was {health}");
+    }
+
+    #[derive(Default, Reflect)]
+    struct ManaMarker;
+
+    #[test]
+    fn test_damage_layer_with_custom_pool() {
+        let mut app = health_test_app();
+        app.add_plugins((
+            range::RangePlugin::<ManaMarker>::default(),
+            DamageLayerPlugin::<ManaMarker>::default(),
+        ));
+        let entity = app
+            .world
+            .spawn((
+                Health::new(0.0, 100.0),
+                range::Range::<ManaMarker>::new(0.0, 30.0),
+                DamageLayers::default()
+                    .with_layer(DamageLayer::new(DamagePool::of::<ManaMarker>())),
+            ))
+            .id();
+
+        app.world.send_event(DamageEvent::new(entity, 50.0));
+        app.update();
+        let mana = app.world.get::<range::Range<ManaMarker>>(entity).unwrap();
+        assert_eq!(mana.get(), 0.0);
+        assert_eq!(app.world.get::<Health>(entity).unwrap().get(), 80.0);
+        assert_eq!(
+            app.world
+                .resource::<Events<range::StartRangeLimitReachedEvent<ManaMarker>>>()
+                .len(),
+            1
+        );
     }
 }
@@ -2,6 +2,51 @@
 //!
 //! Maps use inputs to events.
 //!
+//! Keyboard, mouse and gamepad inputs are supported.  By default, the buttons and
+//! sticks of all connected gamepads are used.  Use `InputMapping::with_gamepad` to
+//! only listen to a specific gamepad.
+//!
+//! For local multiplayer, an InputMapping can also be added as component to the player
+//! entities.  Each player can be assigned to its own input device and the resulting
+//! events contain the player entity.
+//!
+//! Besides the events, the ActionState resource tells which actions are currently
+//! pressed and for how long.  Run conditions like `action_just_pressed` build on it.
+//! Player entities can get their own ActionState component.
+//!
+//! Mappings can be assigned to named input contexts like "menu" or "console".  A
+//! mapping of a context only fires while the context is active in the InputContexts
+//! resource.  Keys bound by an active context with a higher priority are consumed and
+//! do not reach mappings with a lower priority, even if they use another Action type.
+//!
+//! Besides plain buttons, chords like Ctrl+S and temporal patterns like double taps,
+//! holding a button for a while or fighting game sequences can be mapped to actions.
+//!
+//! Each ActionEvent carries a strength between 0.0 and 1.0.  Digital buttons always
+//! have the full strength while analog gamepad buttons like triggers pass their value
+//! once it reaches the actuation threshold.  If several bindings fire the same action,
+//! the ActionCombination of the mapping decides about the strength.
+//!
+//! Touch screens are supported with taps, long presses and swipes as buttons and with
+//! pinch gestures and virtual joysticks in a screen area as sliders.
+//!
+//! Mouse movement can be mapped per motion event with `MouseMove` or with the `Mouse`
+//! slider which sends one event per frame and optionally smooths the movement.  The
+//! `CursorPosition` slider sends the cursor position in window coordinates or, with
+//! the `input_cursor_world` feature, in world coordinates for twin-stick aiming.
+//!
+//! The produced actions can be recorded with the InputRecorder resource and played back
+//! with the InputReplay resource.  During a replay, the real devices are ignored, which
+//! allows to test games in a headless App.
+//!
+//! Bindings can be changed at runtime by sending a RebindRequest which binds the next
+//! pressed key or button to the action.  With the `serialize` feature, the
+//! InputMappingFilePlugin stores the mapping in a RON file and loads it at startup.
+//!
+//! With the `input_asset` feature, the InputMappingAssetPlugin loads the mapping from a
+//! `.input.ron` asset instead.  Whenever the asset is reloaded, for example by the asset
+//! file watcher, it replaces the InputMapping resource.
+//!
 //! ## Example
 //! ```rust
 //! use bevy::prelude::*;
@@ -42,18 +87,83 @@
 //! ```
 
 use bevy::{
-    input::mouse::{self, MouseWheel},
+    ecs::system::SystemParam,
+    input::{
+        mouse::{self, MouseWheel},
+        touch::Touches,
+    },
     prelude::*,
-    utils::hashbrown::HashSet,
+    utils::hashbrown::HashMap,
+    window::PrimaryWindow,
 };
-use std::hash::Hash;
+use std::{hash::Hash, time::Duration};
 
-#[derive(Resource)]
+/// Maps user inputs to actions.
+///
+/// It is either used as resource or as component on player entities for local multiplayer.
+#[derive(Resource, Component, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "input_asset", derive(TypePath))]
 pub struct InputMapping<Action: PartialEq> {
     button_mapping: Vec<ButtonMappingItem<Action>>,
     slider_mapping: Vec<DirectionalSliderMappingItem<Action>>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    device: InputDevice,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    context: Option<String>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    combination: ActionCombination,
 }
 impl<Action: Eq> InputMapping<Action> {
+    /// Create a new input mapping with the given rule for actions fired by several bindings.
+    pub fn with_combination(mut self, combination: ActionCombination) -> Self {
+        self.combination = combination;
+        self
+    }
+    pub fn set_combination(&mut self, combination: ActionCombination) {
+        self.combination = combination;
+    }
+    pub fn get_combination(&self) -> ActionCombination {
+        self.combination
+    }
+
+    /// Create a new input mapping which only fires while the input context is active.
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+    /// Set the input context.  Mappings without context are always active with priority 0.
+    pub fn set_context(&mut self, context: Option<String>) {
+        self.context = context;
+    }
+    pub fn get_context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Create a new input mapping which only listens to the given device.
+    pub fn with_device(mut self, device: InputDevice) -> Self {
+        self.device = device;
+        self
+    }
+    pub fn set_device(&mut self, device: InputDevice) {
+        self.device = device;
+    }
+    pub fn get_device(&self) -> InputDevice {
+        self.device
+    }
+
+    /// Create a new input mapping which only listens to the given gamepad.
+    pub fn with_gamepad(self, gamepad: Gamepad) -> Self {
+        self.with_device(InputDevice::Gamepad(gamepad))
+    }
+    /// Set the gamepad to listen to.  None listens to all devices.
+    pub fn set_gamepad(&mut self, gamepad: Option<Gamepad>) {
+        self.device = gamepad.map_or(InputDevice::All, InputDevice::Gamepad);
+    }
+    pub fn get_gamepad(&self) -> Option<Gamepad> {
+        self.device.gamepad()
+    }
+
     pub fn add_button_mapping(&mut self, item: ButtonMappingItem<Action>) {
         self.button_mapping.push(item)
     }
@@ -70,6 +180,21 @@ impl<Action: Eq> InputMapping<Action> {
         })
     }
 
+    /// Actions which are bound to the same key or button as the input.
+    pub fn find_conflicts(&self, input: &UserButtonInput) -> Vec<&Action> {
+        self.button_mapping
+            .iter()
+            .filter(|item| match input.physical_button() {
+                Some(button) => {
+                    !matches!(item.input, UserButtonInput::Chord(_))
+                        && item.input.physical_button() == Some(button)
+                }
+                None => item.input == *input,
+            })
+            .map(|item| &item.action)
+            .collect()
+    }
+
     pub fn get_mappings_as_slice(&self) -> &[ButtonMappingItem<Action>] {
         &self.button_mapping
     }
@@ -85,6 +210,9 @@ impl<Action: Clone + PartialEq, const N: usize> From<[(UserButtonInput, Action);
         Self {
             button_mapping: item.iter().cloned().map(Into::into).collect(),
             slider_mapping: Vec::new(),
+            device: InputDevice::All,
+            context: None,
+            combination: ActionCombination::default(),
         }
     }
 }
@@ -104,6 +232,9 @@ impl<Action: Clone + PartialEq, const N: usize, const M: usize>
         Self {
             button_mapping: item.0.iter().cloned().map(Into::into).collect(),
             slider_mapping: item.1.iter().cloned().map(Into::into).collect(),
+            device: InputDevice::All,
+            context: None,
+            combination: ActionCombination::default(),
         }
     }
 }
@@ -122,12 +253,104 @@ impl<Action: Clone + PartialEq, const N: usize, const M: usize>
         Self {
             button_mapping: item.0.iter().cloned().map(Into::into).collect(),
             slider_mapping: item.1.iter().cloned().map(Into::into).collect(),
+            device: InputDevice::All,
+            context: None,
+            combination: ActionCombination::default(),
+        }
+    }
+}
+
+/// How the strengths are combined if several bindings fire the same action in a frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActionCombination {
+    /// Use the highest strength.
+    #[default]
+    Max,
+
+    /// Add the strengths and limit the result to 1.0.
+    SumClamped,
+
+    /// Use the strength of the first binding in the mapping.
+    First,
+}
+
+impl ActionCombination {
+    fn combine<Action: Eq + Hash>(
+        self,
+        actions: &mut HashMap<Action, f32>,
+        action: Action,
+        strength: f32,
+    ) {
+        actions
+            .entry(action)
+            .and_modify(|current| {
+                *current = match self {
+                    ActionCombination::Max => current.max(strength),
+                    ActionCombination::SumClamped => (*current + strength).min(1.0),
+                    ActionCombination::First => *current,
+                }
+            })
+            .or_insert(strength);
+    }
+}
+
+/// Actuation threshold of an analog button between 0.0 and 1.0.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Actuation(pub f32);
+
+impl PartialEq for Actuation {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Actuation {}
+
+impl Hash for Actuation {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// The input devices an InputMapping listens to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputDevice {
+    /// Keyboard, mouse, touch screen and all gamepads.
+    #[default]
+    All,
+
+    /// Only keyboard, mouse and touch screen.  Two players can share the keyboard with
+    /// different keys.
+    KeyboardMouse,
+
+    /// Only the given gamepad.
+    Gamepad(Gamepad),
+}
+
+impl InputDevice {
+    pub fn uses_keyboard_mouse(&self) -> bool {
+        matches!(self, InputDevice::All | InputDevice::KeyboardMouse)
+    }
+
+    pub fn uses_gamepads(&self) -> bool {
+        !matches!(self, InputDevice::KeyboardMouse)
+    }
+
+    /// The gamepad if the device is restricted to one.
+    pub fn gamepad(&self) -> Option<Gamepad> {
+        match self {
+            InputDevice::Gamepad(gamepad) => Some(*gamepad),
+            _ => None,
         }
     }
 }
 
 /// Maps a user input to a specific action.
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ButtonMappingItem<Action: PartialEq> {
     pub input: UserButtonInput,
     pub action: Action,
@@ -144,10 +367,12 @@ impl<Action: PartialEq> From<(UserButtonInput, Action)> for ButtonMappingItem<Ac
 
 /// Input types which are either on or off.
 ///
-/// This can be a key on a keyboard, mouse wheel, mouse button, or controller button.
+/// This can be a key on a keyboard, mouse wheel, mouse button, controller button or a
+/// touch gesture.
 ///
 /// The name is a bit weird but ButtonInput shadows a type from Bevy and I want to prevent that.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum UserButtonInput {
     KeyDown(KeyCode),
@@ -155,9 +380,302 @@ pub enum UserButtonInput {
     KeyPressed(KeyCode),
     MouseScrollUp,
     MouseScrollDown,
+    MouseButtonDown(MouseButton),
+    MouseButtonUp(MouseButton),
+    MouseButtonPressed(MouseButton),
+    GamepadButtonDown(GamepadButtonType),
+    GamepadButtonUp(GamepadButtonType),
+    GamepadButtonPressed(GamepadButtonType),
+
+    /// Analog gamepad button which fires while its value reaches the actuation threshold.
+    /// The value is used as strength.
+    GamepadButtonAnalog(GamepadButtonType, Actuation),
+    Chord(Chord),
+
+    /// The input fires twice within the window.
+    DoubleTap {
+        input: Box<UserButtonInput>,
+        window: Duration,
+    },
+
+    /// The button of the input is held for the duration.  Fires once per hold.
+    Hold {
+        input: Box<UserButtonInput>,
+        duration: Duration,
+    },
+
+    /// The button of the input is released after it was held for at least the duration.
+    ReleaseAfterHold {
+        input: Box<UserButtonInput>,
+        duration: Duration,
+    },
+
+    /// The inputs fire in order and each one within the timeout after the previous one.
+    ///
+    /// The steps can be temporal inputs themselves.  Pressing another key or button of
+    /// the device of the expected step restarts the sequence.
+    Sequence {
+        steps: Vec<UserButtonInput>,
+        timeout: Duration,
+    },
+
+    /// A finger touches the screen briefly without moving.
+    TouchTap,
+
+    /// A finger touches the screen for the duration without moving.  Fires once.
+    TouchLongPress(Duration),
+
+    /// A finger moves over the screen in the direction and is released.
+    TouchSwipe(SwipeDirection),
+}
+
+/// Maximum duration of a tap.
+const TOUCH_TAP_DURATION: Duration = Duration::from_millis(300);
+
+/// Distance in pixels which a finger can move during a tap or long press.
+const TOUCH_SLOP: f32 = 20.0;
+
+/// Minimum distance in pixels of a swipe.
+const TOUCH_SWIPE_DISTANCE: f32 = 50.0;
+
+/// Direction of a swipe on the screen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwipeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SwipeDirection {
+    /// Direction of a movement on the screen where y points down.
+    fn from_distance(distance: Vec2) -> Option<Self> {
+        if distance.length() < TOUCH_SWIPE_DISTANCE {
+            None
+        } else if distance.x.abs() > distance.y.abs() {
+            Some(if distance.x > 0.0 {
+                SwipeDirection::Right
+            } else {
+                SwipeDirection::Left
+            })
+        } else if distance.y > 0.0 {
+            Some(SwipeDirection::Down)
+        } else {
+            Some(SwipeDirection::Up)
+        }
+    }
+}
+
+impl UserButtonInput {
+    /// The input fires twice within the window which is in seconds.
+    pub fn double_tap(input: UserButtonInput, window: f32) -> Self {
+        UserButtonInput::DoubleTap {
+            input: Box::new(input),
+            window: Duration::from_secs_f32(window),
+        }
+    }
+
+    /// The button of the input is held for the duration which is in seconds.
+    pub fn hold(input: UserButtonInput, duration: f32) -> Self {
+        UserButtonInput::Hold {
+            input: Box::new(input),
+            duration: Duration::from_secs_f32(duration),
+        }
+    }
+
+    /// The button of the input is released after it was held for the duration which is in seconds.
+    pub fn release_after_hold(input: UserButtonInput, duration: f32) -> Self {
+        UserButtonInput::ReleaseAfterHold {
+            input: Box::new(input),
+            duration: Duration::from_secs_f32(duration),
+        }
+    }
+
+    /// Analog gamepad button which fires once its value reaches the threshold.
+    pub fn gamepad_analog(button_type: GamepadButtonType, threshold: f32) -> Self {
+        UserButtonInput::GamepadButtonAnalog(button_type, Actuation(threshold))
+    }
+
+    /// Analog gamepad trigger which fires once its value reaches the threshold.
+    pub fn gamepad_trigger(side: GamepadSide, threshold: f32) -> Self {
+        Self::gamepad_analog(side.trigger(), threshold)
+    }
+
+    /// A finger touches the screen for the duration which is in seconds.
+    pub fn touch_long_press(duration: f32) -> Self {
+        UserButtonInput::TouchLongPress(Duration::from_secs_f32(duration))
+    }
+
+    /// The inputs fire in order with at most timeout seconds between two steps.
+    pub fn sequence(steps: impl IntoIterator<Item = UserButtonInput>, timeout: f32) -> Self {
+        UserButtonInput::Sequence {
+            steps: steps.into_iter().collect(),
+            timeout: Duration::from_secs_f32(timeout),
+        }
+    }
+
+    /// The input depends on previous frames.
+    fn is_temporal(&self) -> bool {
+        match self {
+            UserButtonInput::DoubleTap { .. }
+            | UserButtonInput::Hold { .. }
+            | UserButtonInput::ReleaseAfterHold { .. }
+            | UserButtonInput::Sequence { .. } => true,
+            UserButtonInput::Chord(chord) => chord.trigger.is_temporal(),
+            _ => false,
+        }
+    }
+
+    /// Collect all keys and buttons which are used by the input and its inner inputs.
+    fn collect_physical_buttons(&self, buttons: &mut Vec<PhysicalButton>) {
+        match self {
+            UserButtonInput::Chord(chord) => {
+                for modifier in chord.modifiers.iter() {
+                    modifier.collect_physical_buttons(buttons);
+                }
+                chord.trigger.collect_physical_buttons(buttons);
+            }
+            UserButtonInput::DoubleTap { input, .. }
+            | UserButtonInput::Hold { input, .. }
+            | UserButtonInput::ReleaseAfterHold { input, .. } => {
+                input.collect_physical_buttons(buttons);
+            }
+            UserButtonInput::Sequence { steps, .. } => {
+                for step in steps.iter() {
+                    step.collect_physical_buttons(buttons);
+                }
+            }
+            _ => buttons.extend(self.physical_button()),
+        }
+    }
+
+    /// The key or button which is used by the input.
+    fn physical_button(&self) -> Option<PhysicalButton> {
+        match self {
+            UserButtonInput::KeyDown(key)
+            | UserButtonInput::KeyUp(key)
+            | UserButtonInput::KeyPressed(key) => Some(PhysicalButton::Key(*key)),
+            UserButtonInput::MouseButtonDown(button)
+            | UserButtonInput::MouseButtonUp(button)
+            | UserButtonInput::MouseButtonPressed(button) => Some(PhysicalButton::Mouse(*button)),
+            UserButtonInput::GamepadButtonDown(button_type)
+            | UserButtonInput::GamepadButtonUp(button_type)
+            | UserButtonInput::GamepadButtonPressed(button_type)
+            | UserButtonInput::GamepadButtonAnalog(button_type, _) => {
+                Some(PhysicalButton::Gamepad(*button_type))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Chord> for UserButtonInput {
+    fn from(chord: Chord) -> Self {
+        UserButtonInput::Chord(chord)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum PhysicalButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+/// Analog input which is read by slider mappings.  Gamepad triggers are claimed as
+/// PhysicalButton because they are buttons as well.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum SliderSource {
+    GamepadStick(GamepadSide),
+    MouseMotion,
+    Cursor,
+    Touch,
+}
+
+impl PhysicalButton {
+    /// Create an input of the same kind as the template which uses this button.
+    ///
+    /// Inputs which are not a plain button result in a pressed input.
+    fn to_input_like(self, template: Option<&UserButtonInput>) -> UserButtonInput {
+        use UserButtonInput::*;
+        match (self, template) {
+            (
+                PhysicalButton::Key(key),
+                Some(KeyDown(_) | MouseButtonDown(_) | GamepadButtonDown(_)),
+            ) => KeyDown(key),
+            (PhysicalButton::Key(key), Some(KeyUp(_) | MouseButtonUp(_) | GamepadButtonUp(_))) => {
+                KeyUp(key)
+            }
+            (PhysicalButton::Key(key), _) => KeyPressed(key),
+            (
+                PhysicalButton::Mouse(button),
+                Some(KeyDown(_) | MouseButtonDown(_) | GamepadButtonDown(_)),
+            ) => MouseButtonDown(button),
+            (
+                PhysicalButton::Mouse(button),
+                Some(KeyUp(_) | MouseButtonUp(_) | GamepadButtonUp(_)),
+            ) => MouseButtonUp(button),
+            (PhysicalButton::Mouse(button), _) => MouseButtonPressed(button),
+            (
+                PhysicalButton::Gamepad(button_type),
+                Some(KeyDown(_) | MouseButtonDown(_) | GamepadButtonDown(_)),
+            ) => GamepadButtonDown(button_type),
+            (
+                PhysicalButton::Gamepad(button_type),
+                Some(KeyUp(_) | MouseButtonUp(_) | GamepadButtonUp(_)),
+            ) => GamepadButtonUp(button_type),
+            (PhysicalButton::Gamepad(button_type), Some(GamepadButtonAnalog(_, threshold))) => {
+                GamepadButtonAnalog(button_type, *threshold)
+            }
+            (PhysicalButton::Gamepad(button_type), _) => GamepadButtonPressed(button_type),
+        }
+    }
+}
+
+/// Combination of inputs like Ctrl+S or "hold LB then press A".
+///
+/// It fires when all modifiers are held and the trigger is active.  The trigger can be
+/// a temporal input like a DoubleTap.  An exclusive chord suppresses all other mappings
+/// of its trigger key while the modifiers are held.
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chord {
+    pub modifiers: Vec<UserButtonInput>,
+    pub trigger: Box<UserButtonInput>,
+    pub exclusive: bool,
+}
+
+impl Chord {
+    pub fn new(
+        modifiers: impl IntoIterator<Item = UserButtonInput>,
+        trigger: UserButtonInput,
+    ) -> Self {
+        Self {
+            modifiers: modifiers.into_iter().collect(),
+            trigger: Box::new(trigger),
+            exclusive: false,
+        }
+    }
+
+    /// Create a new chord which suppresses the other mappings of its trigger.
+    pub fn exclusive(self) -> Self {
+        Self {
+            exclusive: true,
+            ..self
+        }
+    }
+
+    /// All modifiers are held.
+    fn is_engaged(&self, button_states: &ButtonStates) -> bool {
+        self.modifiers
+            .iter()
+            .all(|modifier| button_states.is_held(modifier))
+    }
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct DirectionalSliderMappingItem<Action> {
     pub slider_mapping_type: SliderMappingType,
     pub action: Action,
@@ -169,9 +687,266 @@ pub struct DirectionalSliderMappingItem<Action> {
 ///
 /// This is usually a joystick or mouse movement.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SliderMappingType {
+    /// Mouse movement with one event per motion event.  Motions shorter than the
+    /// deadzone in pixels are ignored.
     MouseMove(f32),
+
+    /// Gamepad stick with the given deadzone.
+    GamepadStick(GamepadSide, f32),
+
+    /// Four buttons combined into a normalized direction.
+    VirtualDpad(VirtualDpad),
+
+    /// Analog gamepad trigger with the given deadzone.  The value is sent in the x direction.
+    GamepadTrigger(GamepadSide, f32),
+
+    /// Mouse movement with deadzone, accumulation and smoothing.
+    Mouse(MouseSlider),
+
+    /// Position of the cursor in the primary window.  It is sent every frame while the
+    /// cursor is inside the window, so the factors should usually be 1.0.
+    CursorPosition(CursorSpace),
+
+    /// Change of the distance between two fingers in pixels, sent in the x direction.
+    /// It is positive if the fingers move apart.
+    TouchPinch,
+
+    /// Virtual joystick for touches which start in a screen area.
+    TouchJoystick(TouchJoystick),
+}
+
+impl SliderMappingType {
+    /// Collect the keys, buttons and analog inputs which are used by the slider.
+    fn collect_sources(&self, buttons: &mut Vec<PhysicalButton>, sliders: &mut Vec<SliderSource>) {
+        match self {
+            SliderMappingType::MouseMove(_) | SliderMappingType::Mouse(_) => {
+                sliders.push(SliderSource::MouseMotion)
+            }
+            SliderMappingType::GamepadStick(side, _) => {
+                sliders.push(SliderSource::GamepadStick(*side))
+            }
+            SliderMappingType::VirtualDpad(dpad) => {
+                for input in [&dpad.up, &dpad.down, &dpad.left, &dpad.right] {
+                    input.collect_physical_buttons(buttons);
+                }
+                if let Some((side, _)) = dpad.stick {
+                    sliders.push(SliderSource::GamepadStick(side));
+                }
+            }
+            SliderMappingType::GamepadTrigger(side, _) => {
+                buttons.push(PhysicalButton::Gamepad(side.trigger()))
+            }
+            SliderMappingType::CursorPosition(_) => sliders.push(SliderSource::Cursor),
+            SliderMappingType::TouchPinch | SliderMappingType::TouchJoystick(_) => {
+                sliders.push(SliderSource::Touch)
+            }
+        }
+    }
+}
+
+/// Virtual joystick on a touch screen.
+///
+/// The joystick is centered where the finger touches the area and reaches its full
+/// value when the finger moved the radius in pixels.  Up is the positive y direction.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchJoystick {
+    /// Area of the screen in logical pixels with the origin in the top left corner.
+    pub area: Rect,
+    pub radius: f32,
+}
+
+impl TouchJoystick {
+    pub fn new(area: Rect, radius: f32) -> Self {
+        Self { area, radius }
+    }
+}
+
+/// Settings of the mouse movement slider.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct MouseSlider {
+    /// Movements shorter than the deadzone in pixels are ignored.
+    pub deadzone: f32,
+
+    /// Send the sum of the motions of a frame as one event instead of one event per motion.
+    pub accumulate: bool,
+
+    /// Part of the previous value which is kept after 1/60 second, between 0.0
+    /// (no smoothing) and 1.0.  The decay is scaled with the frame time, so the
+    /// smoothing feels the same at every frame rate.  Only used if the motions are
+    /// accumulated.
+    pub smoothing: f32,
+
+    pub invert_y: bool,
+}
+
+impl Default for MouseSlider {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.0,
+            accumulate: true,
+            smoothing: 0.0,
+            invert_y: false,
+        }
+    }
+}
+
+impl MouseSlider {
+    /// Create a new mouse slider which accumulates the motions of each frame.
+    pub fn new(deadzone: f32) -> Self {
+        Self {
+            deadzone,
+            ..default()
+        }
+    }
+
+    /// Create a new mouse slider which sends one event per motion event.
+    pub fn per_motion(self) -> Self {
+        Self {
+            accumulate: false,
+            ..self
+        }
+    }
+
+    /// Create a new mouse slider with exponential smoothing.
+    pub fn with_smoothing(self, smoothing: f32) -> Self {
+        Self {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Create a new mouse slider with an inverted y axis.
+    pub fn inverted_y(self) -> Self {
+        Self {
+            invert_y: true,
+            ..self
+        }
+    }
+
+    /// Part of the new value which is taken over within the frame.
+    ///
+    /// Frames without elapsed time, like the first one, are not smoothed, so their
+    /// motion is not lost.
+    fn smoothing_factor(&self, delta_seconds: f32) -> f32 {
+        if self.smoothing <= 0.0 || delta_seconds <= 0.0 {
+            1.0
+        } else {
+            1.0 - self.smoothing.powf(delta_seconds * 60.0)
+        }
+    }
+
+    /// Apply the deadzone and the y inversion to a motion.
+    fn apply(&self, delta: Vec2) -> Option<Vec2> {
+        if delta.length() <= self.deadzone {
+            return None;
+        }
+        let y = if self.invert_y { -delta.y } else { delta.y };
+        Some(Vec2::new(delta.x, y))
+    }
+}
+
+/// Coordinate space of the cursor position slider.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum CursorSpace {
+    /// Logical pixels with the origin in the top left corner of the window.
+    Window,
+
+    /// World coordinates of the first active camera.
+    #[cfg(feature = "input_cursor_world")]
+    World,
+}
+
+/// Direction from four buttons and an optional gamepad stick.
+///
+/// The button direction is normalized, so diagonals are not faster.  The stick is added
+/// and the result is clamped to a length of 1.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct VirtualDpad {
+    pub up: UserButtonInput,
+    pub down: UserButtonInput,
+    pub left: UserButtonInput,
+    pub right: UserButtonInput,
+
+    /// Gamepad stick with its deadzone.
+    pub stick: Option<(GamepadSide, f32)>,
+}
+
+impl VirtualDpad {
+    pub fn new(
+        up: UserButtonInput,
+        down: UserButtonInput,
+        left: UserButtonInput,
+        right: UserButtonInput,
+    ) -> Self {
+        Self {
+            up,
+            down,
+            left,
+            right,
+            stick: None,
+        }
+    }
+
+    pub fn wasd() -> Self {
+        Self::new(
+            UserButtonInput::KeyPressed(KeyCode::KeyW),
+            UserButtonInput::KeyPressed(KeyCode::KeyS),
+            UserButtonInput::KeyPressed(KeyCode::KeyA),
+            UserButtonInput::KeyPressed(KeyCode::KeyD),
+        )
+    }
+
+    pub fn arrow_keys() -> Self {
+        Self::new(
+            UserButtonInput::KeyPressed(KeyCode::ArrowUp),
+            UserButtonInput::KeyPressed(KeyCode::ArrowDown),
+            UserButtonInput::KeyPressed(KeyCode::ArrowLeft),
+            UserButtonInput::KeyPressed(KeyCode::ArrowRight),
+        )
+    }
+
+    pub fn gamepad_dpad() -> Self {
+        Self::new(
+            UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadUp),
+            UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadDown),
+            UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadLeft),
+            UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadRight),
+        )
+    }
+
+    /// Create a new virtual dpad which also uses the gamepad stick.
+    pub fn with_stick(self, side: GamepadSide, deadzone: f32) -> Self {
+        Self {
+            stick: Some((side, deadzone)),
+            ..self
+        }
+    }
+}
+
+/// Side of the gamepad for sticks and triggers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadSide {
+    Left,
+    Right,
+}
+
+impl GamepadSide {
+    /// The analog trigger on this side.
+    fn trigger(self) -> GamepadButtonType {
+        match self {
+            GamepadSide::Left => GamepadButtonType::LeftTrigger2,
+            GamepadSide::Right => GamepadButtonType::RightTrigger2,
+        }
+    }
 }
 
 impl<Action> From<(SliderMappingType, Action, f32)> for DirectionalSliderMappingItem<Action> {
@@ -198,6 +973,12 @@ impl<Action> From<(SliderMappingType, Action, f32, f32)> for DirectionalSliderMa
 #[derive(Event)]
 pub struct ActionEvent<Action> {
     pub action: Action,
+
+    /// Strength between 0.0 and 1.0.  Digital buttons always have the strength 1.0.
+    pub strength: f32,
+
+    /// Player entity whose InputMapping produced the event.  None for the InputMapping resource.
+    pub player: Option<Entity>,
 }
 
 #[derive(Event)]
@@ -205,74 +986,1602 @@ pub struct DirectionSliderEvent<Action> {
     pub action: Action,
     pub x: f32,
     pub y: f32,
+
+    /// Player entity whose InputMapping produced the event.  None for the InputMapping resource.
+    pub player: Option<Entity>,
 }
 
-pub fn input_mapping_system<Action: Clone + Eq + Hash + Send + Sync + 'static>(
-    input: Res<bevy::prelude::ButtonInput<KeyCode>>,
-    mut scroll_events: EventReader<MouseWheel>,
-    mut motion_events: EventReader<mouse::MouseMotion>,
-    mut mapping: ResMut<InputMapping<Action>>,
-    mut key_event_writer: EventWriter<ActionEvent<Action>>,
-    mut direction_slider_event_writer: EventWriter<DirectionSliderEvent<Action>>,
-    mut actions: Local<HashSet<Action>>,
-) {
-    let mut scroll_up = false;
-    let mut scroll_down = false;
+/// System set of the systems which map the user inputs to actions.
+///
+/// Systems which read the ActionEvents or the ActionState should run after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputMappingSystem;
 
-    for scroll_event in scroll_events.read() {
-        if scroll_event.y < 0.0 {
-            scroll_up = true;
-        } else if scroll_event.y > 0.0 {
-            scroll_down = true;
+#[derive(Debug, Clone, Default)]
+struct ActionData {
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+    pressed_since: Duration,
+    strength: f32,
+}
+
+/// Current state of all actions.
+///
+/// An action is pressed as long as one of its inputs fires.  It is updated by the
+/// input_mapping_system as resource for the InputMapping resource and as component
+/// for player entities with an InputMapping.
+#[derive(Resource, Component, Debug, Clone)]
+pub struct ActionState<Action> {
+    actions: HashMap<Action, ActionData>,
+    axes: HashMap<Action, Vec2>,
+    now: Duration,
+}
+
+impl<Action> Default for ActionState<Action> {
+    fn default() -> Self {
+        Self {
+            actions: HashMap::new(),
+            axes: HashMap::new(),
+            now: Duration::ZERO,
         }
     }
+}
 
-    for item in mapping.button_mapping.iter_mut() {
-        match item.input {
-            UserButtonInput::KeyDown(key) if input.just_pressed(key) => {
-                actions.insert(item.action.clone());
-            }
-            UserButtonInput::KeyUp(key) if input.just_released(key) => {
-                actions.insert(item.action.clone());
-            }
-            UserButtonInput::KeyPressed(key) if input.pressed(key) => {
-                actions.insert(item.action.clone());
-            }
-            UserButtonInput::MouseScrollUp if scroll_up => {
-                actions.insert(item.action.clone());
-            }
-            UserButtonInput::MouseScrollDown if scroll_down => {
-                actions.insert(item.action.clone());
-            }
-            _ => {}
-        }
+impl<Action: Eq + Hash + Clone> ActionState<Action> {
+    pub fn pressed(&self, action: &Action) -> bool {
+        self.actions.get(action).is_some_and(|data| data.pressed)
     }
 
-    for action in actions.iter() {
-        key_event_writer.send(ActionEvent {
-            action: action.clone(),
-        });
+    pub fn just_pressed(&self, action: &Action) -> bool {
+        self.actions
+            .get(action)
+            .is_some_and(|data| data.just_pressed)
     }
-    actions.clear();
 
-    if !mapping.slider_mapping.is_empty() {
-        for event in motion_events.read() {
-            for action in mapping.slider_mapping.iter() {
-                direction_slider_event_writer.send(DirectionSliderEvent {
-                    action: action.action.clone(),
-                    x: event.delta.x * action.factor_x,
-                    y: event.delta.y * action.factor_y,
+    pub fn just_released(&self, action: &Action) -> bool {
+        self.actions
+            .get(action)
+            .is_some_and(|data| data.just_released)
+    }
+
+    /// Strength of the action in the current frame or 0.0 if it is not pressed.
+    pub fn strength(&self, action: &Action) -> f32 {
+        match self.actions.get(action) {
+            Some(data) if data.pressed => data.strength,
+            _ => 0.0,
+        }
+    }
+
+    /// Seconds since the action is pressed or 0 if it is not pressed.
+    pub fn held_duration(&self, action: &Action) -> f32 {
+        match self.actions.get(action) {
+            Some(data) if data.pressed => (self.now - data.pressed_since).as_secs_f32(),
+            _ => 0.0,
+        }
+    }
+
+    /// Sum of the slider values of the action in the current frame.
+    pub fn axis(&self, action: &Action) -> Vec2 {
+        self.axes.get(action).copied().unwrap_or(Vec2::ZERO)
+    }
+
+    /// Set the slider values of the current frame.  Values of the same action are added.
+    pub fn set_axes(&mut self, axes: impl IntoIterator<Item = (Action, Vec2)>) {
+        self.axes.clear();
+        for (action, value) in axes {
+            *self.axes.entry(action).or_default() += value;
+        }
+    }
+
+    /// Iterate over all pressed actions.
+    pub fn get_pressed(&self) -> impl Iterator<Item = &Action> {
+        self.actions
+            .iter()
+            .filter(|(_, data)| data.pressed)
+            .map(|(action, _)| action)
+    }
+
+    /// Set the actions which are pressed in the current frame with their strength.
+    pub fn update(&mut self, pressed: &HashMap<Action, f32>, now: Duration) {
+        self.now = now;
+        for (action, data) in self.actions.iter_mut() {
+            let strength = pressed.get(action);
+            let is_pressed = strength.is_some();
+            data.just_pressed = !data.pressed && is_pressed;
+            data.just_released = data.pressed && !is_pressed;
+            data.pressed = is_pressed;
+            data.strength = strength.copied().unwrap_or(0.0);
+            if data.just_pressed {
+                data.pressed_since = now;
+            }
+        }
+        for (action, strength) in pressed.iter() {
+            if !self.actions.contains_key(action) {
+                self.actions.insert(
+                    action.clone(),
+                    ActionData {
+                        pressed: true,
+                        just_pressed: true,
+                        just_released: false,
+                        pressed_since: now,
+                        strength: *strength,
+                    },
+                );
+            }
+        }
+        self.actions
+            .retain(|_, data| data.pressed || data.just_released);
+    }
+}
+
+/// Run condition which is true while the action is pressed.
+pub fn action_pressed<Action: Eq + Hash + Clone + Send + Sync + 'static>(
+    action: Action,
+) -> impl Fn(Option<Res<ActionState<Action>>>) -> bool + Clone {
+    move |state: Option<Res<ActionState<Action>>>| state.is_some_and(|state| state.pressed(&action))
+}
+
+/// Run condition which is true in the frame the action starts.
+pub fn action_just_pressed<Action: Eq + Hash + Clone + Send + Sync + 'static>(
+    action: Action,
+) -> impl Fn(Option<Res<ActionState<Action>>>) -> bool + Clone {
+    move |state: Option<Res<ActionState<Action>>>| {
+        state.is_some_and(|state| state.just_pressed(&action))
+    }
+}
+
+/// Run condition which is true in the frame the action stops.
+pub fn action_just_released<Action: Eq + Hash + Clone + Send + Sync + 'static>(
+    action: Action,
+) -> impl Fn(Option<Res<ActionState<Action>>>) -> bool + Clone {
+    move |state: Option<Res<ActionState<Action>>>| {
+        state.is_some_and(|state| state.just_released(&action))
+    }
+}
+
+/// Named layer of input mappings.
+///
+/// A blocking context consumes all inputs, so mappings with a lower priority receive
+/// nothing while it is active.  Otherwise only the keys, buttons, sticks, mouse motion,
+/// cursor and touches which are used by the mappings of the context are consumed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputContext {
+    pub name: String,
+    pub priority: i32,
+    pub blocking: bool,
+}
+
+impl InputContext {
+    pub fn new(name: impl Into<String>, priority: i32) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            blocking: false,
+        }
+    }
+
+    /// Create a new context which consumes all inputs.
+    pub fn blocking(self) -> Self {
+        Self {
+            blocking: true,
+            ..self
+        }
+    }
+}
+
+/// The currently active input contexts.
+#[derive(Resource, Debug, Default)]
+pub struct InputContexts {
+    contexts: Vec<InputContext>,
+}
+
+impl InputContexts {
+    /// Activate the context.  An active context with the same name is replaced.
+    pub fn push(&mut self, context: InputContext) {
+        self.remove(&context.name);
+        self.contexts.push(context);
+    }
+
+    /// Deactivate the context which was pushed last.
+    pub fn pop(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    /// Deactivate the context with the given name.
+    pub fn remove(&mut self, name: &str) -> Option<InputContext> {
+        let index = self
+            .contexts
+            .iter()
+            .position(|context| context.name == name)?;
+        Some(self.contexts.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&InputContext> {
+        self.contexts.iter().find(|context| context.name == name)
+    }
+
+    pub fn is_active(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Priority of a mapping with the context or None if the context is not active.
+    fn mapping_priority(&self, context: Option<&str>) -> Option<i32> {
+        match context {
+            Some(name) => self.get(name).map(|context| context.priority),
+            None => Some(0),
+        }
+    }
+}
+
+/// System which activates the input context, for example in `OnEnter` of a state.
+pub fn push_input_context(context: InputContext) -> impl Fn(ResMut<InputContexts>) + Clone {
+    move |mut contexts: ResMut<InputContexts>| contexts.push(context.clone())
+}
+
+/// System which deactivates the input context, for example in `OnExit` of a state.
+pub fn remove_input_context(name: impl Into<String>) -> impl Fn(ResMut<InputContexts>) + Clone {
+    let name = name.into();
+    move |mut contexts: ResMut<InputContexts>| {
+        contexts.remove(&name);
+    }
+}
+
+/// Keys, buttons and analog inputs bound by the active mappings with the highest priority
+/// which uses them.
+///
+/// It is filled by the claim_inputs_system of each Action type before the inputs are mapped.
+#[derive(Resource, Debug, Default)]
+pub struct InputClaims {
+    buttons: HashMap<PhysicalButton, i32>,
+    sliders: HashMap<SliderSource, i32>,
+    blocking: Option<i32>,
+}
+
+impl InputClaims {
+    fn claim(&mut self, button: PhysicalButton, priority: i32) {
+        let claimed = self.buttons.entry(button).or_insert(priority);
+        *claimed = (*claimed).max(priority);
+    }
+
+    fn claim_slider(&mut self, source: SliderSource, priority: i32) {
+        let claimed = self.sliders.entry(source).or_insert(priority);
+        *claimed = (*claimed).max(priority);
+    }
+
+    fn is_consumed(&self, button: PhysicalButton, priority: i32) -> bool {
+        self.buttons
+            .get(&button)
+            .is_some_and(|claimed| *claimed > priority)
+    }
+
+    fn is_slider_consumed(&self, source: SliderSource, priority: i32) -> bool {
+        self.sliders
+            .get(&source)
+            .is_some_and(|claimed| *claimed > priority)
+    }
+
+    fn is_blocked(&self, priority: i32) -> bool {
+        self.blocking.is_some_and(|blocking| blocking > priority)
+    }
+}
+
+/// System set of the systems which fill the InputClaims.  It runs before InputMappingSystem.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputClaimSystem;
+
+pub fn clear_input_claims_system(contexts: Res<InputContexts>, mut claims: ResMut<InputClaims>) {
+    claims.buttons.clear();
+    claims.sliders.clear();
+    claims.blocking = contexts
+        .contexts
+        .iter()
+        .filter(|context| context.blocking)
+        .map(|context| context.priority)
+        .max();
+}
+
+/// Claims the keys, buttons and analog inputs of the active mappings of the Action type.
+pub fn claim_inputs_system<Action: Eq + Send + Sync + 'static>(
+    contexts: Res<InputContexts>,
+    mut claims: ResMut<InputClaims>,
+    mapping: Option<Res<InputMapping<Action>>>,
+    player_mappings: Query<&InputMapping<Action>>,
+) {
+    let mut buttons = Vec::new();
+    let mut sliders = Vec::new();
+    for mapping in mapping.as_deref().into_iter().chain(player_mappings.iter()) {
+        let Some(priority) = contexts.mapping_priority(mapping.get_context()) else {
+            continue;
+        };
+        buttons.clear();
+        sliders.clear();
+        for item in mapping.button_mapping.iter() {
+            item.input.collect_physical_buttons(&mut buttons);
+        }
+        for item in mapping.slider_mapping.iter() {
+            item.slider_mapping_type
+                .collect_sources(&mut buttons, &mut sliders);
+        }
+        for button in buttons.iter() {
+            claims.claim(*button, priority);
+        }
+        for source in sliders.iter() {
+            claims.claim_slider(*source, priority);
+        }
+    }
+}
+
+/// Shared resources and systems of the input contexts which are used by all Action types.
+pub struct InputContextPlugin;
+
+impl Plugin for InputContextPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputContexts>()
+            .init_resource::<InputClaims>()
+            .configure_sets(Update, InputClaimSystem.before(InputMappingSystem))
+            .add_systems(First, clear_input_claims_system);
+    }
+}
+
+/// Remembers when the touches started and the distance of the pinch.
+#[derive(Default)]
+pub struct TouchTracker {
+    starts: HashMap<u64, Duration>,
+    pinch_distance: Option<f32>,
+}
+
+impl TouchTracker {
+    /// Track the new touches and return the change of the pinch distance.
+    fn update(&mut self, touches: &Touches, now: Duration) -> f32 {
+        self.starts
+            .retain(|id, _| touches.get_pressed(*id).is_some() || touches.just_released(*id));
+        for touch in touches.iter().chain(touches.iter_just_released()) {
+            self.starts.entry(touch.id()).or_insert(now);
+        }
+
+        let mut pressed: Vec<_> = touches.iter().collect();
+        pressed.sort_by_key(|touch| touch.id());
+        let distance = match pressed.as_slice() {
+            [first, second, ..] => Some(first.position().distance(second.position())),
+            _ => None,
+        };
+        let pinch = match (self.pinch_distance, distance) {
+            (Some(previous), Some(distance)) => distance - previous,
+            _ => 0.0,
+        };
+        self.pinch_distance = distance;
+        pinch
+    }
+
+    /// Time since the touch started.
+    fn duration(&self, id: u64, now: Duration) -> Duration {
+        self.starts
+            .get(&id)
+            .map_or(Duration::ZERO, |start| now.saturating_sub(*start))
+    }
+}
+
+/// All user inputs which are read by the input mapping system.
+#[derive(SystemParam)]
+pub struct UserInputs<'w, 's> {
+    keys: Res<'w, bevy::prelude::ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, bevy::prelude::ButtonInput<MouseButton>>,
+    gamepad_inputs: GamepadInputs<'w>,
+    scroll_events: EventReader<'w, 's, MouseWheel>,
+    motion_events: EventReader<'w, 's, mouse::MouseMotion>,
+    touches: Res<'w, Touches>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    #[cfg(feature = "input_cursor_world")]
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl<'w, 's> UserInputs<'w, 's> {
+    /// Cursor position in the primary window.
+    fn cursor_position(&self) -> Option<Vec2> {
+        self.windows.get_single().ok()?.cursor_position()
+    }
+
+    /// Cursor position in world coordinates of the first active camera.
+    #[cfg(feature = "input_cursor_world")]
+    fn cursor_world_position(&self) -> Option<Vec2> {
+        let cursor = self.cursor_position()?;
+        self.cameras
+            .iter()
+            .filter(|(camera, _)| camera.is_active)
+            .find_map(|(camera, transform)| camera.viewport_to_world_2d(transform, cursor))
+    }
+}
+
+/// Access to the buttons and axes of the connected gamepads.
+#[derive(SystemParam)]
+pub struct GamepadInputs<'w> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, bevy::prelude::ButtonInput<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+    button_axes: Res<'w, Axis<GamepadButton>>,
+}
+
+impl<'w> GamepadInputs<'w> {
+    /// Iterate over the connected gamepads which match the filter.
+    pub fn iter(&self, filter: Option<Gamepad>) -> impl Iterator<Item = Gamepad> + '_ {
+        self.gamepads
+            .iter()
+            .filter(move |gamepad| filter.is_none() || filter == Some(*gamepad))
+    }
+
+    pub fn just_pressed(&self, filter: Option<Gamepad>, button_type: GamepadButtonType) -> bool {
+        self.iter(filter).any(|gamepad| {
+            self.buttons
+                .just_pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    /// Any button which was just pressed on a gamepad which matches the filter.
+    pub fn any_just_pressed(&self, filter: Option<Gamepad>) -> Option<GamepadButtonType> {
+        self.buttons
+            .get_just_pressed()
+            .find(|button| filter.is_none() || filter == Some(button.gamepad))
+            .map(|button| button.button_type)
+    }
+
+    pub fn just_released(&self, filter: Option<Gamepad>, button_type: GamepadButtonType) -> bool {
+        self.iter(filter).any(|gamepad| {
+            self.buttons
+                .just_released(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    pub fn pressed(&self, filter: Option<Gamepad>, button_type: GamepadButtonType) -> bool {
+        self.iter(filter).any(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    /// Position of the stick which is pushed the most and outside of the deadzone.
+    pub fn stick(&self, filter: Option<Gamepad>, side: GamepadSide, deadzone: f32) -> Option<Vec2> {
+        let (axis_x, axis_y) = match side {
+            GamepadSide::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            GamepadSide::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        };
+        self.iter(filter)
+            .map(|gamepad| {
+                Vec2::new(
+                    self.axes
+                        .get(GamepadAxis::new(gamepad, axis_x))
+                        .unwrap_or(0.0),
+                    self.axes
+                        .get(GamepadAxis::new(gamepad, axis_y))
+                        .unwrap_or(0.0),
+                )
+            })
+            .filter(|position| position.length() > deadzone)
+            .max_by(|a, b| a.length().total_cmp(&b.length()))
+    }
+
+    /// Value of the trigger which is pressed the most and outside of the deadzone.
+    pub fn trigger(
+        &self,
+        filter: Option<Gamepad>,
+        side: GamepadSide,
+        deadzone: f32,
+    ) -> Option<f32> {
+        self.analog(filter, side.trigger())
+            .filter(|value| *value > deadzone)
+    }
+
+    /// Highest value of the analog button of the matching gamepads.
+    pub fn analog(&self, filter: Option<Gamepad>, button_type: GamepadButtonType) -> Option<f32> {
+        self.iter(filter)
+            .filter_map(|gamepad| {
+                self.button_axes
+                    .get(GamepadButton::new(gamepad, button_type))
+            })
+            .max_by(f32::total_cmp)
+    }
+}
+
+/// State of all button inputs in the current frame.
+struct ButtonStates<'a> {
+    keys: &'a bevy::prelude::ButtonInput<KeyCode>,
+    mouse_buttons: &'a bevy::prelude::ButtonInput<MouseButton>,
+    gamepad_inputs: &'a GamepadInputs<'a>,
+    device: InputDevice,
+    scroll_up: bool,
+    scroll_down: bool,
+    cursor_position: Option<Vec2>,
+    #[cfg(feature = "input_cursor_world")]
+    cursor_world_position: Option<Vec2>,
+    touches: &'a Touches,
+    touch_tracker: &'a TouchTracker,
+    pinch: f32,
+    now: Duration,
+    delta: Duration,
+    claims: &'a InputClaims,
+
+    /// Priority of the mapping or None if its context is not active or blocked.
+    priority: Option<i32>,
+}
+
+impl<'a> ButtonStates<'a> {
+    /// The input fires in this frame.
+    fn is_active(&self, input: &UserButtonInput) -> bool {
+        if self.priority.is_none() {
+            return false;
+        }
+        if let Some(button) = input.physical_button() {
+            if !self.uses_button(button) {
+                return false;
+            }
+        }
+        let gamepad = self.device.gamepad();
+        match input {
+            UserButtonInput::KeyDown(key) => self.keys.just_pressed(*key),
+            UserButtonInput::KeyUp(key) => self.keys.just_released(*key),
+            UserButtonInput::KeyPressed(key) => self.keys.pressed(*key),
+            UserButtonInput::MouseScrollUp => self.device.uses_keyboard_mouse() && self.scroll_up,
+            UserButtonInput::MouseScrollDown => {
+                self.device.uses_keyboard_mouse() && self.scroll_down
+            }
+            UserButtonInput::MouseButtonDown(button) => self.mouse_buttons.just_pressed(*button),
+            UserButtonInput::MouseButtonUp(button) => self.mouse_buttons.just_released(*button),
+            UserButtonInput::MouseButtonPressed(button) => self.mouse_buttons.pressed(*button),
+            UserButtonInput::GamepadButtonDown(button_type) => {
+                self.gamepad_inputs.just_pressed(gamepad, *button_type)
+            }
+            UserButtonInput::GamepadButtonUp(button_type) => {
+                self.gamepad_inputs.just_released(gamepad, *button_type)
+            }
+            UserButtonInput::GamepadButtonPressed(button_type) => {
+                self.gamepad_inputs.pressed(gamepad, *button_type)
+            }
+            UserButtonInput::GamepadButtonAnalog(..) => self.strength(input).is_some(),
+            UserButtonInput::Chord(chord) => {
+                chord.is_engaged(self) && self.is_active(&chord.trigger)
+            }
+            UserButtonInput::DoubleTap { .. }
+            | UserButtonInput::Hold { .. }
+            | UserButtonInput::ReleaseAfterHold { .. }
+            | UserButtonInput::Sequence { .. } => false,
+            UserButtonInput::TouchTap => {
+                self.device.uses_keyboard_mouse()
+                    && self.touches.iter_just_released().any(|touch| {
+                        touch.distance().length() <= TOUCH_SLOP
+                            && self.touch_tracker.duration(touch.id(), self.now)
+                                <= TOUCH_TAP_DURATION
+                    })
+            }
+            UserButtonInput::TouchLongPress(duration) => {
+                self.device.uses_keyboard_mouse()
+                    && self.touches.iter().any(|touch| {
+                        let held = self.touch_tracker.duration(touch.id(), self.now);
+                        touch.distance().length() <= TOUCH_SLOP
+                            && held >= *duration
+                            && held.saturating_sub(self.delta) < *duration
+                    })
+            }
+            UserButtonInput::TouchSwipe(direction) => {
+                self.device.uses_keyboard_mouse()
+                    && self.touches.iter_just_released().any(|touch| {
+                        SwipeDirection::from_distance(touch.distance()) == Some(*direction)
+                    })
+            }
+        }
+    }
+
+    /// Strength of the input if it is active.
+    fn strength(&self, input: &UserButtonInput) -> Option<f32> {
+        match input {
+            UserButtonInput::GamepadButtonAnalog(button_type, Actuation(threshold)) => {
+                if !self.uses_button(PhysicalButton::Gamepad(*button_type)) {
+                    return None;
+                }
+                self.gamepad_inputs
+                    .analog(self.device.gamepad(), *button_type)
+                    .filter(|value| *value > 0.0 && *value >= *threshold)
+            }
+            UserButtonInput::Chord(chord) => chord
+                .is_engaged(self)
+                .then(|| self.strength(&chord.trigger))
+                .flatten(),
+            _ => self.is_active(input).then_some(1.0),
+        }
+    }
+
+    /// Advance the state of a temporal input and return if it fires in this frame.
+    fn is_pattern_active(
+        &self,
+        input: &UserButtonInput,
+        state: &mut PatternState,
+        now: Duration,
+    ) -> bool {
+        match input {
+            UserButtonInput::DoubleTap { input, window } => {
+                if !self.is_pattern_active(input, state.inner(0), now) {
+                    false
+                } else if state.since.is_some_and(|since| now - since <= *window) {
+                    state.since = None;
+                    true
+                } else {
+                    state.since = Some(now);
+                    false
+                }
+            }
+            UserButtonInput::Hold { input, duration } => {
+                if self.is_held(input) {
+                    let since = *state.since.get_or_insert(now);
+                    if !state.fired && now - since >= *duration {
+                        state.fired = true;
+                        return true;
+                    }
+                } else {
+                    state.since = None;
+                    state.fired = false;
+                }
+                false
+            }
+            UserButtonInput::ReleaseAfterHold { input, duration } => {
+                if self.is_held(input) {
+                    state.since.get_or_insert(now);
+                    false
+                } else {
+                    state
+                        .since
+                        .take()
+                        .is_some_and(|since| now - since >= *duration)
+                }
+            }
+            UserButtonInput::Sequence { steps, timeout } => {
+                if state.step > 0 && state.since.is_some_and(|since| now - since > *timeout) {
+                    state.restart();
+                }
+                let Some(step) = steps.get(state.step) else {
+                    return false;
+                };
+                if !self.is_pattern_active(step, state.inner(state.step), now) {
+                    if state.step > 0 && self.other_button_just_pressed(step) {
+                        state.restart();
+                        return self.is_pattern_active(input, state, now);
+                    }
+                    return false;
+                }
+                state.step += 1;
+                state.since = Some(now);
+                if state.step == steps.len() {
+                    state.restart();
+                    true
+                } else {
+                    false
+                }
+            }
+            UserButtonInput::Chord(chord) => {
+                let fired = self.is_pattern_active(&chord.trigger, state.inner(0), now);
+                fired && chord.is_engaged(self)
+            }
+            _ => self.is_active(input),
+        }
+    }
+
+    /// A key or button which is not used by the input was just pressed on the device of
+    /// the input.
+    fn other_button_just_pressed(&self, input: &UserButtonInput) -> bool {
+        let mut buttons = Vec::new();
+        input.collect_physical_buttons(&mut buttons);
+        let is_other =
+            |button: PhysicalButton| !buttons.contains(&button) && self.uses_button(button);
+        let uses_gamepad = buttons
+            .iter()
+            .any(|button| matches!(button, PhysicalButton::Gamepad(_)));
+        let uses_keyboard_mouse = buttons
+            .iter()
+            .any(|button| !matches!(button, PhysicalButton::Gamepad(_)));
+        let gamepad = self.device.gamepad();
+        (uses_keyboard_mouse
+            && (self
+                .keys
+                .get_just_pressed()
+                .any(|key| is_other(PhysicalButton::Key(*key)))
+                || self
+                    .mouse_buttons
+                    .get_just_pressed()
+                    .any(|button| is_other(PhysicalButton::Mouse(*button)))))
+            || (uses_gamepad
+                && self
+                    .gamepad_inputs
+                    .buttons
+                    .get_just_pressed()
+                    .filter(|button| gamepad.is_none() || gamepad == Some(button.gamepad))
+                    .any(|button| is_other(PhysicalButton::Gamepad(button.button_type))))
+    }
+
+    /// The key or button of the input is currently held down.
+    fn is_held(&self, input: &UserButtonInput) -> bool {
+        match input.physical_button() {
+            Some(button) if !self.uses_button(button) => false,
+            Some(PhysicalButton::Key(key)) => self.keys.pressed(key),
+            Some(PhysicalButton::Mouse(button)) => self.mouse_buttons.pressed(button),
+            Some(PhysicalButton::Gamepad(button_type)) => self
+                .gamepad_inputs
+                .pressed(self.device.gamepad(), button_type),
+            None => self.is_active(input),
+        }
+    }
+
+    /// Value of a slider which does not depend on mouse events.
+    fn slider_value(&self, slider_mapping_type: &SliderMappingType) -> Option<Vec2> {
+        self.priority?;
+        let gamepad = self.device.gamepad();
+        match slider_mapping_type {
+            SliderMappingType::GamepadStick(side, deadzone)
+                if self.uses_slider(SliderSource::GamepadStick(*side)) =>
+            {
+                self.gamepad_inputs.stick(gamepad, *side, *deadzone)
+            }
+            SliderMappingType::GamepadTrigger(side, deadzone)
+                if self.uses_button(PhysicalButton::Gamepad(side.trigger())) =>
+            {
+                self.gamepad_inputs
+                    .trigger(gamepad, *side, *deadzone)
+                    .map(|value| Vec2::new(value, 0.0))
+            }
+            SliderMappingType::VirtualDpad(dpad) => {
+                let axis = |positive: &UserButtonInput, negative: &UserButtonInput| {
+                    self.is_active(positive) as i32 as f32 - self.is_active(negative) as i32 as f32
+                };
+                let buttons = Vec2::new(axis(&dpad.right, &dpad.left), axis(&dpad.up, &dpad.down))
+                    .normalize_or_zero();
+                let stick = dpad
+                    .stick
+                    .filter(|(side, _)| self.uses_slider(SliderSource::GamepadStick(*side)))
+                    .and_then(|(side, deadzone)| self.gamepad_inputs.stick(gamepad, side, deadzone))
+                    .unwrap_or(Vec2::ZERO);
+                let value = (buttons + stick).clamp_length_max(1.0);
+                (value != Vec2::ZERO).then_some(value)
+            }
+            SliderMappingType::CursorPosition(space) if self.uses_slider(SliderSource::Cursor) => {
+                match space {
+                    CursorSpace::Window => self.cursor_position,
+                    #[cfg(feature = "input_cursor_world")]
+                    CursorSpace::World => self.cursor_world_position,
+                }
+            }
+            SliderMappingType::TouchPinch if self.uses_slider(SliderSource::Touch) => {
+                (self.pinch != 0.0).then_some(Vec2::new(self.pinch, 0.0))
+            }
+            SliderMappingType::TouchJoystick(joystick) if self.uses_slider(SliderSource::Touch) => {
+                self.touches
+                    .iter()
+                    .find(|touch| joystick.area.contains(touch.start_position()))
+                    .map(|touch| {
+                        let distance = touch.distance() / joystick.radius.max(f32::EPSILON);
+                        Vec2::new(distance.x, -distance.y).clamp_length_max(1.0)
+                    })
+            }
+            _ => None,
+        }
+    }
+
+    /// The analog input belongs to the device and is not consumed by a mapping with a higher
+    /// priority.
+    fn uses_slider(&self, source: SliderSource) -> bool {
+        let Some(priority) = self.priority else {
+            return false;
+        };
+        let device_matches = match source {
+            SliderSource::GamepadStick(_) => self.device.uses_gamepads(),
+            SliderSource::MouseMotion | SliderSource::Cursor | SliderSource::Touch => {
+                self.device.uses_keyboard_mouse()
+            }
+        };
+        device_matches && !self.claims.is_slider_consumed(source, priority)
+    }
+
+    /// The button belongs to the device and is not consumed by a mapping with a higher priority.
+    fn uses_button(&self, button: PhysicalButton) -> bool {
+        let Some(priority) = self.priority else {
+            return false;
+        };
+        let device_matches = match button {
+            PhysicalButton::Key(_) | PhysicalButton::Mouse(_) => self.device.uses_keyboard_mouse(),
+            PhysicalButton::Gamepad(_) => self.device.uses_gamepads(),
+        };
+        device_matches && !self.claims.is_consumed(button, priority)
+    }
+}
+
+/// Progress of a temporal input.
+#[derive(Default)]
+pub struct PatternState {
+    since: Option<Duration>,
+    fired: bool,
+    step: usize,
+
+    /// Progress of the inner inputs, like the steps of a sequence.
+    inner: Vec<PatternState>,
+}
+
+impl PatternState {
+    /// Progress of the inner input with the index.
+    fn inner(&mut self, index: usize) -> &mut PatternState {
+        if self.inner.len() <= index {
+            self.inner.resize_with(index + 1, PatternState::default);
+        }
+        &mut self.inner[index]
+    }
+
+    /// Start a sequence from its first step again.
+    fn restart(&mut self) {
+        self.step = 0;
+        self.inner.clear();
+    }
+}
+
+/// Maps the user inputs to events for the InputMapping resource and all player mappings.
+#[allow(clippy::too_many_arguments)]
+pub fn input_mapping_system<Action: Clone + Eq + Hash + Send + Sync + 'static>(
+    mut user_inputs: UserInputs,
+    mapping: Option<Res<InputMapping<Action>>>,
+    player_mappings: Query<(Entity, &InputMapping<Action>)>,
+    mut key_event_writer: EventWriter<ActionEvent<Action>>,
+    mut direction_slider_event_writer: EventWriter<DirectionSliderEvent<Action>>,
+    mut actions: Local<HashMap<Action, f32>>,
+    mut pattern_states: Local<HashMap<(Option<Entity>, UserButtonInput), PatternState>>,
+    mut action_state: ResMut<ActionState<Action>>,
+    mut player_action_states: Query<&mut ActionState<Action>>,
+    mut smoothed_motions: Local<HashMap<(Option<Entity>, usize), Vec2>>,
+    mut touch_tracker: Local<TouchTracker>,
+    contexts: Res<InputContexts>,
+    claims: Res<InputClaims>,
+    time: Res<Time>,
+) {
+    let mut scroll_up = false;
+    let mut scroll_down = false;
+
+    for scroll_event in user_inputs.scroll_events.read() {
+        if scroll_event.y < 0.0 {
+            scroll_up = true;
+        } else if scroll_event.y > 0.0 {
+            scroll_down = true;
+        }
+    }
+    let motions: Vec<Vec2> = user_inputs
+        .motion_events
+        .read()
+        .map(|event| event.delta)
+        .collect();
+    let pinch = touch_tracker.update(&user_inputs.touches, time.elapsed());
+    let cursor_position = user_inputs.cursor_position();
+    #[cfg(feature = "input_cursor_world")]
+    let cursor_world_position = user_inputs.cursor_world_position();
+
+    let mappings = mapping
+        .as_deref()
+        .map(|mapping| (None, mapping))
+        .into_iter()
+        .chain(
+            player_mappings
+                .iter()
+                .map(|(player, mapping)| (Some(player), mapping)),
+        );
+    let mut pattern_results: HashMap<(Option<Entity>, &UserButtonInput), bool> = HashMap::new();
+    for (player, mapping) in mappings {
+        let device = mapping.device;
+        let priority = contexts
+            .mapping_priority(mapping.get_context())
+            .filter(|priority| !claims.is_blocked(*priority));
+        let button_states = ButtonStates {
+            keys: &user_inputs.keys,
+            mouse_buttons: &user_inputs.mouse_buttons,
+            gamepad_inputs: &user_inputs.gamepad_inputs,
+            device,
+            scroll_up,
+            scroll_down,
+            cursor_position,
+            #[cfg(feature = "input_cursor_world")]
+            cursor_world_position,
+            touches: &user_inputs.touches,
+            touch_tracker: &touch_tracker,
+            pinch,
+            now: time.elapsed(),
+            delta: time.delta(),
+            claims: &claims,
+            priority,
+        };
+        let suppressed: Vec<PhysicalButton> = mapping
+            .button_mapping
+            .iter()
+            .filter_map(|item| match &item.input {
+                UserButtonInput::Chord(chord)
+                    if chord.exclusive && chord.is_engaged(&button_states) =>
+                {
+                    chord.trigger.physical_button()
+                }
+                _ => None,
+            })
+            .collect();
+        for item in mapping.button_mapping.iter() {
+            if item.input.is_temporal() {
+                let active = *pattern_results
+                    .entry((player, &item.input))
+                    .or_insert_with(|| {
+                        let state = pattern_states
+                            .entry((player, item.input.clone()))
+                            .or_default();
+                        button_states.is_pattern_active(&item.input, state, time.elapsed())
+                    });
+                if active {
+                    mapping
+                        .combination
+                        .combine(&mut actions, item.action.clone(), 1.0);
+                }
+                continue;
+            }
+            let is_suppressed = !matches!(item.input, UserButtonInput::Chord(_))
+                && item
+                    .input
+                    .physical_button()
+                    .is_some_and(|button| suppressed.contains(&button));
+            if is_suppressed {
+                continue;
+            }
+            if let Some(strength) = button_states.strength(&item.input) {
+                mapping
+                    .combination
+                    .combine(&mut actions, item.action.clone(), strength);
+            }
+        }
+
+        let mut slider_values: Vec<(Action, Vec2)> = Vec::new();
+        let uses_mouse = button_states.uses_slider(SliderSource::MouseMotion);
+        for (index, item) in mapping.slider_mapping.iter().enumerate() {
+            let factor = Vec2::new(item.factor_x, item.factor_y);
+            match &item.slider_mapping_type {
+                SliderMappingType::MouseMove(deadzone) => {
+                    if uses_mouse {
+                        let slider = MouseSlider::new(*deadzone).per_motion();
+                        slider_values.extend(
+                            motions
+                                .iter()
+                                .filter_map(|delta| slider.apply(*delta))
+                                .map(|delta| (item.action.clone(), delta * factor)),
+                        );
+                    }
+                }
+                SliderMappingType::Mouse(slider) if !slider.accumulate => {
+                    if uses_mouse {
+                        slider_values.extend(
+                            motions
+                                .iter()
+                                .filter_map(|delta| slider.apply(*delta))
+                                .map(|delta| (item.action.clone(), delta * factor)),
+                        );
+                    }
+                }
+                SliderMappingType::Mouse(slider) => {
+                    let delta = if uses_mouse {
+                        slider.apply(motions.iter().sum()).unwrap_or(Vec2::ZERO)
+                    } else {
+                        Vec2::ZERO
+                    };
+                    let smoothed = smoothed_motions.entry((player, index)).or_default();
+                    *smoothed = smoothed.lerp(delta, slider.smoothing_factor(time.delta_seconds()));
+                    if smoothed.length() < 0.001 {
+                        *smoothed = Vec2::ZERO;
+                    } else {
+                        slider_values.push((item.action.clone(), *smoothed * factor));
+                    }
+                }
+                slider_mapping_type => {
+                    if let Some(value) = button_states.slider_value(slider_mapping_type) {
+                        slider_values.push((item.action.clone(), value * factor));
+                    }
+                }
+            }
+        }
+
+        let state = match player {
+            Some(player) => player_action_states
+                .get_mut(player)
+                .ok()
+                .map(Mut::into_inner),
+            None => Some(&mut *action_state),
+        };
+        if let Some(state) = state {
+            state.update(&actions, time.elapsed());
+            state.set_axes(slider_values.iter().cloned());
+        }
+        for (action, strength) in actions.drain() {
+            key_event_writer.send(ActionEvent {
+                action,
+                strength,
+                player,
+            });
+        }
+        for (action, value) in slider_values {
+            direction_slider_event_writer.send(DirectionSliderEvent {
+                action,
+                x: value.x,
+                y: value.y,
+                player,
+            });
+        }
+    }
+    pattern_states.retain(|(player, input), _| pattern_results.contains_key(&(*player, input)));
+}
+
+/// What happens when a rebound input is already used by another action.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RebindConflictPolicy {
+    /// Do not bind the input and send a conflict event.
+    #[default]
+    Reject,
+
+    /// Bind the input and keep the other bindings.
+    Allow,
+
+    /// Bind the input and remove it from the other actions.
+    Steal,
+}
+
+/// Request to bind the next pressed key or button to the action.
+///
+/// If slider is set, the next moved gamepad stick or trigger is captured instead and
+/// replaces the gamepad slider mappings of the action.
+#[derive(Event, Clone)]
+pub struct RebindRequest<Action> {
+    pub action: Action,
+
+    /// Existing binding of the action which is replaced.  The captured input is of the same
+    /// kind, so a KeyDown binding is replaced by a down binding.
+    pub replace: Option<UserButtonInput>,
+    pub slider: bool,
+    pub conflict_policy: RebindConflictPolicy,
+
+    /// Player entity whose InputMapping is changed.  None changes the InputMapping resource.
+    pub player: Option<Entity>,
+}
+
+impl<Action> RebindRequest<Action> {
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            replace: None,
+            slider: false,
+            conflict_policy: RebindConflictPolicy::default(),
+            player: None,
+        }
+    }
+
+    /// Create a new request which changes the InputMapping of the player entity.
+    pub fn for_player(self, player: Entity) -> Self {
+        Self {
+            player: Some(player),
+            ..self
+        }
+    }
+
+    /// Create a new request which replaces the existing binding.
+    pub fn replacing(self, input: UserButtonInput) -> Self {
+        Self {
+            replace: Some(input),
+            ..self
+        }
+    }
+
+    /// Create a new request which captures a gamepad stick or trigger.
+    pub fn slider(self) -> Self {
+        Self {
+            slider: true,
+            ..self
+        }
+    }
+
+    pub fn with_conflict_policy(self, conflict_policy: RebindConflictPolicy) -> Self {
+        Self {
+            conflict_policy,
+            ..self
+        }
+    }
+}
+
+/// Rebind request which waits for the next user input.
+///
+/// Remove the request to cancel the rebinding.
+#[derive(Resource)]
+pub struct PendingRebind<Action> {
+    pub request: Option<RebindRequest<Action>>,
+}
+
+impl<Action> Default for PendingRebind<Action> {
+    fn default() -> Self {
+        Self { request: None }
+    }
+}
+
+/// Result of a RebindRequest.
+#[derive(Event, Clone)]
+pub enum RebindEvent<Action> {
+    /// The input is now bound to the action.
+    Bound {
+        action: Action,
+        input: UserButtonInput,
+    },
+
+    /// The gamepad stick or trigger is now bound to the action.
+    SliderBound {
+        action: Action,
+        slider: SliderMappingType,
+    },
+
+    /// The input is already used by other actions and was not bound.
+    Conflict {
+        action: Action,
+        input: UserButtonInput,
+        conflicts: Vec<Action>,
+    },
+}
+
+/// Binds the next user input to the action of the pending rebind request.
+#[allow(clippy::too_many_arguments)]
+pub fn rebind_system<Action: Clone + Eq + Hash + Send + Sync + 'static>(
+    keys: Res<bevy::prelude::ButtonInput<KeyCode>>,
+    mouse_buttons: Res<bevy::prelude::ButtonInput<MouseButton>>,
+    gamepad_inputs: GamepadInputs,
+    mut requests: EventReader<RebindRequest<Action>>,
+    mut pending: ResMut<PendingRebind<Action>>,
+    mut mapping: Option<ResMut<InputMapping<Action>>>,
+    mut player_mappings: Query<&mut InputMapping<Action>>,
+    mut rebind_event_writer: EventWriter<RebindEvent<Action>>,
+) {
+    if let Some(request) = requests.read().last() {
+        pending.request = Some(request.clone());
+        return;
+    }
+    let Some(request) = pending.request.as_ref() else {
+        return;
+    };
+    let current = match request.player {
+        Some(player) => player_mappings.get(player).ok(),
+        None => mapping.as_deref(),
+    };
+    let Some(current) = current else {
+        pending.request = None;
+        return;
+    };
+    let device = current.device;
+    let gamepad = device.gamepad();
+
+    if request.slider {
+        if !device.uses_gamepads() {
+            return;
+        }
+        let slider = [GamepadSide::Left, GamepadSide::Right]
+            .into_iter()
+            .find_map(|side| {
+                if gamepad_inputs.stick(gamepad, side, 0.5).is_some() {
+                    Some(SliderMappingType::GamepadStick(side, 0.1))
+                } else if gamepad_inputs.trigger(gamepad, side, 0.5).is_some() {
+                    Some(SliderMappingType::GamepadTrigger(side, 0.1))
+                } else {
+                    None
+                }
+            });
+        let Some(slider) = slider else {
+            return;
+        };
+        let request = pending.request.take().unwrap();
+        let Some(mut mapping) = rebind_target(request.player, &mut mapping, &mut player_mappings)
+        else {
+            return;
+        };
+        let mut factors = None;
+        mapping.slider_mapping.retain(|item| {
+            let is_replaced = item.action == request.action
+                && matches!(
+                    item.slider_mapping_type,
+                    SliderMappingType::GamepadStick(..) | SliderMappingType::GamepadTrigger(..)
+                );
+            if is_replaced {
+                factors = Some((item.factor_x, item.factor_y));
+            }
+            !is_replaced
+        });
+        let (factor_x, factor_y) = factors.unwrap_or((1.0, 1.0));
+        mapping.add_directional_mapping(
+            (slider.clone(), request.action.clone(), factor_x, factor_y).into(),
+        );
+        rebind_event_writer.send(RebindEvent::SliderBound {
+            action: request.action,
+            slider,
+        });
+        return;
+    }
+
+    let keyboard_mouse_button = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| PhysicalButton::Key(*key))
+        .or_else(|| {
+            mouse_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| PhysicalButton::Mouse(*button))
+        })
+        .filter(|_| device.uses_keyboard_mouse());
+    let button = keyboard_mouse_button.or_else(|| {
+        gamepad_inputs
+            .any_just_pressed(gamepad)
+            .filter(|_| device.uses_gamepads())
+            .map(PhysicalButton::Gamepad)
+    });
+    let Some(button) = button else {
+        return;
+    };
+    let request = pending.request.take().unwrap();
+    let input = button.to_input_like(request.replace.as_ref());
+    let conflicts: Vec<Action> = current
+        .find_conflicts(&input)
+        .into_iter()
+        .filter(|action| **action != request.action)
+        .cloned()
+        .collect();
+    if !conflicts.is_empty() {
+        match request.conflict_policy {
+            RebindConflictPolicy::Reject => {
+                rebind_event_writer.send(RebindEvent::Conflict {
+                    action: request.action,
+                    input,
+                    conflicts,
                 });
+                return;
+            }
+            RebindConflictPolicy::Allow | RebindConflictPolicy::Steal => {}
+        }
+    }
+    let Some(mut mapping) = rebind_target(request.player, &mut mapping, &mut player_mappings)
+    else {
+        return;
+    };
+    if request.conflict_policy == RebindConflictPolicy::Steal {
+        for conflict in conflicts.iter() {
+            mapping.button_mapping.retain(|item| {
+                item.action != *conflict || item.input.physical_button() != input.physical_button()
+            });
+        }
+    }
+    if let Some(replace) = request.replace.as_ref() {
+        mapping.remove_button_mapping(&ButtonMappingItem {
+            input: replace.clone(),
+            action: request.action.clone(),
+        });
+    }
+    mapping.add_button_mapping((input.clone(), request.action.clone()).into());
+    rebind_event_writer.send(RebindEvent::Bound {
+        action: request.action,
+        input,
+    });
+}
+
+/// The mapping which is changed by a rebind request.
+///
+/// It is only borrowed mutably once the input was captured, so a pending request does not
+/// mark the mapping as changed.
+fn rebind_target<'a, Action: PartialEq + Send + Sync + 'static>(
+    player: Option<Entity>,
+    mapping: &'a mut Option<ResMut<InputMapping<Action>>>,
+    player_mappings: &'a mut Query<&mut InputMapping<Action>>,
+) -> Option<Mut<'a, InputMapping<Action>>> {
+    match player {
+        Some(player) => player_mappings.get_mut(player).ok(),
+        None => mapping.as_mut().map(|mapping| mapping.reborrow()),
+    }
+}
+
+pub struct InputMappingPlugin<Action> {
+    __action: std::marker::PhantomData<Action>,
+}
+
+impl<Action> Default for InputMappingPlugin<Action> {
+    fn default() -> Self {
+        Self {
+            __action: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Action: Clone + Eq + Hash + Send + Sync + 'static> Plugin for InputMappingPlugin<Action> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ActionEvent<Action>>()
+            .add_event::<DirectionSliderEvent<Action>>()
+            .add_event::<RebindRequest<Action>>()
+            .add_event::<RebindEvent<Action>>()
+            .init_resource::<PendingRebind<Action>>()
+            .init_resource::<ActionState<Action>>()
+            .add_systems(
+                Update,
+                (
+                    claim_inputs_system::<Action>.in_set(InputClaimSystem),
+                    (
+                        input_mapping_system::<Action>
+                            .run_if(not(resource_exists::<InputReplay<Action>>)),
+                        replay_input_system::<Action>
+                            .run_if(resource_exists::<InputReplay<Action>>),
+                        rebind_system::<Action>,
+                        record_input_system::<Action>
+                            .run_if(resource_exists::<InputRecorder<Action>>),
+                    )
+                        .chain()
+                        .in_set(InputMappingSystem),
+                ),
+            );
+        if !app.is_plugin_added::<InputContextPlugin>() {
+            app.add_plugins(InputContextPlugin);
+        }
+    }
+}
+
+/// Actions and slider values which were produced in one frame.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedFrame<Action> {
+    /// Number of the frame since the recording started.
+    pub frame: u32,
+
+    /// Actions with their strength.
+    pub actions: Vec<(Action, f32)>,
+    pub sliders: Vec<(Action, Vec2)>,
+}
+
+/// Recorded actions of the InputMapping resource.  Frames without actions are skipped.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputRecording<Action> {
+    pub frames: Vec<RecordedFrame<Action>>,
+}
+
+impl<Action> Default for InputRecording<Action> {
+    fn default() -> Self {
+        Self { frames: Vec::new() }
+    }
+}
+
+impl<Action> InputRecording<Action> {
+    /// Get the recorded frame with the given number.
+    pub fn get_frame(&self, frame: u32) -> Option<&RecordedFrame<Action>> {
+        let index = self
+            .frames
+            .binary_search_by_key(&frame, |recorded| recorded.frame)
+            .ok()?;
+        self.frames.get(index)
+    }
+
+    /// Number of frames until the last recorded action.
+    pub fn len(&self) -> u32 {
+        self.frames.last().map_or(0, |recorded| recorded.frame + 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// Records the actions and slider events of the InputMapping resource while it exists.
+///
+/// Events of player mappings are not recorded since the player entities differ between runs.
+#[derive(Resource)]
+pub struct InputRecorder<Action> {
+    recording: InputRecording<Action>,
+    frame: u32,
+}
+
+impl<Action> Default for InputRecorder<Action> {
+    fn default() -> Self {
+        Self {
+            recording: InputRecording::default(),
+            frame: 0,
+        }
+    }
+}
+
+impl<Action> InputRecorder<Action> {
+    pub fn recording(&self) -> &InputRecording<Action> {
+        &self.recording
+    }
+
+    pub fn into_recording(self) -> InputRecording<Action> {
+        self.recording
+    }
+}
+
+/// Plays back a recording instead of reading the real devices while it exists.
+///
+/// The first recorded frame is sent in the first update after the resource was inserted.
+#[derive(Resource)]
+pub struct InputReplay<Action> {
+    recording: InputRecording<Action>,
+    frame: u32,
+}
+
+impl<Action> InputReplay<Action> {
+    pub fn new(recording: InputRecording<Action>) -> Self {
+        Self {
+            recording,
+            frame: 0,
+        }
+    }
+
+    /// Number of the frame which is played next.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// All recorded frames were played.
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.len()
+    }
+}
+
+pub fn record_input_system<Action: Clone + Send + Sync + 'static>(
+    mut recorder: ResMut<InputRecorder<Action>>,
+    mut action_events: EventReader<ActionEvent<Action>>,
+    mut slider_events: EventReader<DirectionSliderEvent<Action>>,
+) {
+    let frame = recorder.frame;
+    recorder.frame += 1;
+    let actions: Vec<(Action, f32)> = action_events
+        .read()
+        .filter(|event| event.player.is_none())
+        .map(|event| (event.action.clone(), event.strength))
+        .collect();
+    let sliders: Vec<(Action, Vec2)> = slider_events
+        .read()
+        .filter(|event| event.player.is_none())
+        .map(|event| (event.action.clone(), Vec2::new(event.x, event.y)))
+        .collect();
+    if !actions.is_empty() || !sliders.is_empty() {
+        recorder.recording.frames.push(RecordedFrame {
+            frame,
+            actions,
+            sliders,
+        });
+    }
+}
+
+pub fn replay_input_system<Action: Clone + Eq + Hash + Send + Sync + 'static>(
+    mut replay: ResMut<InputReplay<Action>>,
+    mut key_event_writer: EventWriter<ActionEvent<Action>>,
+    mut direction_slider_event_writer: EventWriter<DirectionSliderEvent<Action>>,
+    mut action_state: ResMut<ActionState<Action>>,
+    time: Res<Time>,
+) {
+    let frame = replay.frame;
+    replay.frame += 1;
+    let Some(recorded) = replay.recording.get_frame(frame) else {
+        action_state.update(&HashMap::new(), time.elapsed());
+        action_state.set_axes([]);
+        return;
+    };
+    let actions: HashMap<Action, f32> = recorded.actions.iter().cloned().collect();
+    action_state.update(&actions, time.elapsed());
+    action_state.set_axes(recorded.sliders.iter().cloned());
+    for (action, strength) in recorded.actions.iter() {
+        key_event_writer.send(ActionEvent {
+            action: action.clone(),
+            strength: *strength,
+            player: None,
+        });
+    }
+    for (action, value) in recorded.sliders.iter() {
+        direction_slider_event_writer.send(DirectionSliderEvent {
+            action: action.clone(),
+            x: value.x,
+            y: value.y,
+            player: None,
+        });
+    }
+}
+
+/// Error while reading or writing an input mapping or recording file.
+#[cfg(feature = "serialize")]
+#[derive(Debug)]
+pub enum InputMappingFileError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Deserialize(ron::error::SpannedError),
+}
+
+#[cfg(feature = "serialize")]
+impl std::fmt::Display for InputMappingFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputMappingFileError::Io(err) => {
+                write!(f, "Could not access input file: {err}")
+            }
+            InputMappingFileError::Serialize(err) => {
+                write!(f, "Could not serialize input data: {err}")
+            }
+            InputMappingFileError::Deserialize(err) => {
+                write!(
+                    f,
+                    "Invalid input data in line {}, column {}: {}",
+                    err.position.line, err.position.col, err.code
+                )
             }
         }
     }
 }
 
-pub struct InputMappingPlugin<Action> {
+#[cfg(feature = "serialize")]
+impl std::error::Error for InputMappingFileError {}
+
+#[cfg(feature = "serialize")]
+impl<Action: PartialEq + serde::Serialize> InputMapping<Action> {
+    /// Serialize the mapping into a RON string.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// Write the mapping to a RON file.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), InputMappingFileError> {
+        let ron = self.to_ron().map_err(InputMappingFileError::Serialize)?;
+        std::fs::write(path, ron).map_err(InputMappingFileError::Io)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<Action: PartialEq + serde::de::DeserializeOwned> InputMapping<Action> {
+    /// Deserialize a mapping from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    /// Read the mapping from a RON file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, InputMappingFileError> {
+        let ron = std::fs::read_to_string(path).map_err(InputMappingFileError::Io)?;
+        Self::from_ron(&ron).map_err(InputMappingFileError::Deserialize)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<Action: serde::Serialize + serde::de::DeserializeOwned> InputRecording<Action> {
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    /// Write the recording to a RON file.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), InputMappingFileError> {
+        let ron = self.to_ron().map_err(InputMappingFileError::Serialize)?;
+        std::fs::write(path, ron).map_err(InputMappingFileError::Io)
+    }
+
+    /// Read the recording from a RON file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, InputMappingFileError> {
+        let ron = std::fs::read_to_string(path).map_err(InputMappingFileError::Io)?;
+        Self::from_ron(&ron).map_err(InputMappingFileError::Deserialize)
+    }
+}
+
+#[cfg(feature = "input_asset")]
+impl<Action: PartialEq + TypePath + Send + Sync> bevy::asset::Asset for InputMapping<Action> {}
+
+#[cfg(feature = "input_asset")]
+impl<Action: PartialEq> bevy::asset::VisitAssetDependencies for InputMapping<Action> {
+    fn visit_dependencies(&self, _visit: &mut impl FnMut(bevy::asset::UntypedAssetId)) {}
+}
+
+/// Loads InputMapping assets from `.input.ron` files.
+#[cfg(feature = "input_asset")]
+pub struct InputMappingLoader<Action> {
     __action: std::marker::PhantomData<Action>,
 }
 
-impl<Action> Default for InputMappingPlugin<Action> {
+#[cfg(feature = "input_asset")]
+impl<Action> Default for InputMappingLoader<Action> {
     fn default() -> Self {
         Self {
             __action: std::marker::PhantomData,
@@ -280,17 +2589,175 @@ impl<Action> Default for InputMappingPlugin<Action> {
     }
 }
 
-impl<Action: Clone + Eq + Hash + Send + Sync + 'static> Plugin for InputMappingPlugin<Action> {
+#[cfg(feature = "input_asset")]
+impl<Action> bevy::asset::AssetLoader for InputMappingLoader<Action>
+where
+    Action: PartialEq + TypePath + serde::de::DeserializeOwned + Send + Sync,
+{
+    type Asset = InputMapping<Action>;
+    type Settings = ();
+    type Error = InputMappingFileError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a (),
+        _load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::utils::BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        use bevy::asset::AsyncReadExt;
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(InputMappingFileError::Io)?;
+            ron::de::from_bytes(&bytes).map_err(InputMappingFileError::Deserialize)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["input.ron"]
+    }
+}
+
+/// Handle of the InputMapping asset which is used as InputMapping resource.
+#[cfg(feature = "input_asset")]
+#[derive(Resource)]
+pub struct InputMappingHandle<Action: PartialEq + TypePath + Send + Sync>(
+    pub Handle<InputMapping<Action>>,
+);
+
+/// Replaces the InputMapping resource whenever its asset is loaded or modified.
+#[cfg(feature = "input_asset")]
+pub fn apply_input_mapping_asset_system<Action: Clone + PartialEq + TypePath + Send + Sync>(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<InputMapping<Action>>>,
+    handle: Option<Res<InputMappingHandle<Action>>>,
+    assets: Res<Assets<InputMapping<Action>>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if let Some(mapping) = assets.get(&handle.0) {
+                commands.insert_resource(mapping.clone());
+            }
+        }
+    }
+}
+
+/// Loads the InputMapping resource from an asset and keeps it up to date on hot reload.
+///
+/// Syntax errors are logged by the asset server with the line of the error.  Until the
+/// asset is loaded, the default mapping stays active.  The Action type must implement
+/// TypePath.
+#[cfg(feature = "input_asset")]
+pub struct InputMappingAssetPlugin<Action> {
+    pub path: String,
+    __action: std::marker::PhantomData<Action>,
+}
+
+#[cfg(feature = "input_asset")]
+impl<Action> InputMappingAssetPlugin<Action> {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            __action: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "input_asset")]
+impl<Action> Plugin for InputMappingAssetPlugin<Action>
+where
+    Action: Clone + PartialEq + TypePath + serde::de::DeserializeOwned + Send + Sync,
+{
     fn build(&self, app: &mut App) {
-        app.add_event::<ActionEvent<Action>>()
-            .add_event::<DirectionSliderEvent<Action>>()
-            .add_systems(Update, input_mapping_system::<Action>);
+        let path = self.path.clone();
+        app.init_asset::<InputMapping<Action>>()
+            .init_asset_loader::<InputMappingLoader<Action>>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                    commands.insert_resource(InputMappingHandle::<Action>(
+                        asset_server.load(path.clone()),
+                    ));
+                },
+            )
+            .add_systems(PreUpdate, apply_input_mapping_asset_system::<Action>);
+    }
+}
+
+/// Loads the InputMapping from a RON file at startup and saves it whenever it changes.
+///
+/// The file is loaded in PostStartup, so it overrides the default mappings inserted
+/// during Startup.  If the file does not exist, the default mapping is kept.  Inserting
+/// or loading the mapping does not write the file, only later changes do.
+#[cfg(feature = "serialize")]
+pub struct InputMappingFilePlugin<Action> {
+    pub path: std::path::PathBuf,
+    __action: std::marker::PhantomData<Action>,
+}
+
+#[cfg(feature = "serialize")]
+impl<Action> InputMappingFilePlugin<Action> {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            __action: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<Action> Plugin for InputMappingFilePlugin<Action>
+where
+    Action: Eq + Send + Sync + serde::Serialize + serde::de::DeserializeOwned + 'static,
+{
+    fn build(&self, app: &mut App) {
+        let load_path = self.path.clone();
+        let save_path = self.path.clone();
+        app.add_systems(
+            PostStartup,
+            move |mut commands: Commands, mapping: Option<ResMut<InputMapping<Action>>>| {
+                if !load_path.exists() {
+                    return;
+                }
+                match (InputMapping::<Action>::load(&load_path), mapping) {
+                    (Ok(loaded), Some(mut mapping)) => *mapping.bypass_change_detection() = loaded,
+                    (Ok(loaded), None) => commands.insert_resource(loaded),
+                    (Err(err), _) => bevy::log::error!("{}: {}", load_path.display(), err),
+                }
+            },
+        )
+        .add_systems(
+            Last,
+            (move |mapping: Res<InputMapping<Action>>| {
+                if mapping.is_added() {
+                    return;
+                }
+                if let Err(err) = mapping.save(&save_path) {
+                    bevy::log::error!("{}: {}", save_path.display(), err);
+                }
+            })
+            .run_if(resource_exists_and_changed::<InputMapping<Action>>),
+        );
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bevy::input::gamepad::{
+        GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection,
+        GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+    };
+    use bevy::input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        mouse::MouseButtonInput,
+        ButtonState,
+    };
 
     #[test]
     fn input_adding_and_removing_mappings() {
@@ -339,4 +2806,1081 @@ mod tests {
         assert_eq!(1, mapping.get_mappings_as_slice().len());
         assert_eq!(0, mapping.get_directional_mappings_as_slice().len());
     }
+
+    fn input_test_app(mapping: InputMapping<i32>) -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(bevy::input::InputPlugin)
+            .add_plugins(InputMappingPlugin::<i32>::default())
+            .insert_resource(mapping);
+        app
+    }
+
+    fn press(app: &mut App, key_code: KeyCode, state: ButtonState) {
+        app.world.send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    fn read_actions(app: &mut App) -> Vec<i32> {
+        app.update();
+        let mut actions: Vec<i32> = app
+            .world
+            .resource_mut::<Events<ActionEvent<i32>>>()
+            .drain()
+            .map(|event| event.action)
+            .collect();
+        actions.sort();
+        actions
+    }
+
+    #[test]
+    fn input_gamepad_buttons_and_sticks() {
+        let gamepad = Gamepad::new(0);
+        let other_gamepad = Gamepad::new(1);
+        let mapping: InputMapping<i32> = (
+            [
+                (
+                    UserButtonInput::GamepadButtonDown(GamepadButtonType::South),
+                    1,
+                ),
+                (
+                    UserButtonInput::GamepadButtonPressed(GamepadButtonType::East),
+                    2,
+                ),
+            ],
+            [(
+                SliderMappingType::GamepadStick(GamepadSide::Left, 0.2),
+                3,
+                1.0,
+            )],
+        )
+            .into();
+        let mut app = input_test_app(mapping.with_gamepad(gamepad));
+
+        for connected in [gamepad, other_gamepad] {
+            app.world
+                .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                    connected,
+                    GamepadConnection::Connected(GamepadInfo {
+                        name: "Test".into(),
+                    }),
+                )));
+        }
+        app.update();
+
+        app.world
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                gamepad,
+                GamepadButtonType::South,
+                1.0,
+            )));
+        app.world
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                other_gamepad,
+                GamepadButtonType::East,
+                1.0,
+            )));
+        app.world
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                gamepad,
+                GamepadAxisType::LeftStickX,
+                0.1,
+            )));
+        assert_eq!(read_actions(&mut app), vec![1]);
+        assert!(app
+            .world
+            .resource::<Events<DirectionSliderEvent<i32>>>()
+            .is_empty());
+
+        app.world
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                gamepad,
+                GamepadAxisType::LeftStickX,
+                0.5,
+            )));
+        app.update();
+        let sliders: Vec<(f32, f32)> = app
+            .world
+            .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+            .drain()
+            .map(|event| (event.x, event.y))
+            .collect();
+        assert_eq!(sliders, vec![(0.5, 0.0)]);
+    }
+
+    #[test]
+    fn input_mouse_buttons() {
+        let mut app = input_test_app(
+            [
+                (UserButtonInput::MouseButtonDown(MouseButton::Left), 1),
+                (UserButtonInput::MouseButtonUp(MouseButton::Left), 2),
+                (UserButtonInput::MouseButtonPressed(MouseButton::Left), 3),
+                (UserButtonInput::MouseButtonPressed(MouseButton::Right), 4),
+            ]
+            .into(),
+        );
+        let send_left = |app: &mut App, state: ButtonState| {
+            app.world.send_event(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+                window: Entity::PLACEHOLDER,
+            });
+        };
+
+        send_left(&mut app, ButtonState::Pressed);
+        assert_eq!(read_actions(&mut app), vec![1, 3]);
+        assert_eq!(read_actions(&mut app), vec![3]);
+        send_left(&mut app, ButtonState::Released);
+        assert_eq!(read_actions(&mut app), vec![2]);
+        assert!(read_actions(&mut app).is_empty());
+    }
+
+    #[test]
+    fn input_chords() {
+        let mut app = input_test_app(
+            [
+                (UserButtonInput::KeyDown(KeyCode::KeyS), 1),
+                (
+                    Chord::new(
+                        [UserButtonInput::KeyPressed(KeyCode::ControlLeft)],
+                        UserButtonInput::KeyDown(KeyCode::KeyS),
+                    )
+                    .exclusive()
+                    .into(),
+                    2,
+                ),
+                (UserButtonInput::KeyDown(KeyCode::KeyA), 3),
+                (
+                    Chord::new(
+                        [UserButtonInput::KeyPressed(KeyCode::ShiftLeft)],
+                        UserButtonInput::KeyDown(KeyCode::KeyA),
+                    )
+                    .into(),
+                    4,
+                ),
+            ]
+            .into(),
+        );
+        press(&mut app, KeyCode::KeyS, ButtonState::Pressed);
+        press(&mut app, KeyCode::KeyA, ButtonState::Pressed);
+        assert_eq!(read_actions(&mut app), vec![1, 3]);
+
+        press(&mut app, KeyCode::KeyS, ButtonState::Released);
+        press(&mut app, KeyCode::KeyA, ButtonState::Released);
+        press(&mut app, KeyCode::ControlLeft, ButtonState::Pressed);
+        press(&mut app, KeyCode::ShiftLeft, ButtonState::Pressed);
+        assert!(read_actions(&mut app).is_empty());
+
+        press(&mut app, KeyCode::KeyS, ButtonState::Pressed);
+        press(&mut app, KeyCode::KeyA, ButtonState::Pressed);
+        assert_eq!(read_actions(&mut app), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn input_temporal_patterns() {
+        let mut app = input_test_app(
+            [
+                (
+                    UserButtonInput::double_tap(UserButtonInput::KeyDown(KeyCode::KeyA), 0.3),
+                    1,
+                ),
+                (
+                    UserButtonInput::hold(UserButtonInput::KeyPressed(KeyCode::KeyH), 1.0),
+                    2,
+                ),
+                (
+                    UserButtonInput::release_after_hold(
+                        UserButtonInput::KeyPressed(KeyCode::KeyH),
+                        1.0,
+                    ),
+                    3,
+                ),
+                (
+                    UserButtonInput::sequence(
+                        [
+                            UserButtonInput::KeyDown(KeyCode::ArrowDown),
+                            UserButtonInput::KeyDown(KeyCode::ArrowRight),
+                            UserButtonInput::KeyDown(KeyCode::KeyP),
+                        ],
+                        0.5,
+                    ),
+                    4,
+                ),
+            ]
+            .into(),
+        );
+        let step = |app: &mut App, key_code: KeyCode, state: ButtonState, seconds: f32| {
+            press(app, key_code, state);
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+            read_actions(app)
+        };
+        let tap = |app: &mut App, key_code: KeyCode, seconds: f32| {
+            let mut actions = step(app, key_code, ButtonState::Pressed, seconds / 2.0);
+            actions.extend(step(app, key_code, ButtonState::Released, seconds / 2.0));
+            actions
+        };
+
+        assert!(tap(&mut app, KeyCode::KeyA, 0.2).is_empty());
+        assert_eq!(tap(&mut app, KeyCode::KeyA, 0.2), vec![1]);
+        assert!(tap(&mut app, KeyCode::KeyA, 0.4).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyA, 0.4).is_empty());
+
+        assert!(tap(&mut app, KeyCode::KeyH, 0.5).is_empty());
+        assert!(step(&mut app, KeyCode::KeyH, ButtonState::Pressed, 0.6).is_empty());
+        assert!(step(&mut app, KeyCode::KeyH, ButtonState::Pressed, 0.6).is_empty());
+        assert_eq!(
+            step(&mut app, KeyCode::KeyH, ButtonState::Pressed, 0.6),
+            vec![2]
+        );
+        assert!(step(&mut app, KeyCode::KeyH, ButtonState::Pressed, 0.6).is_empty());
+        assert_eq!(
+            step(&mut app, KeyCode::KeyH, ButtonState::Released, 0.6),
+            vec![3]
+        );
+
+        assert!(tap(&mut app, KeyCode::ArrowDown, 0.2).is_empty());
+        assert!(tap(&mut app, KeyCode::ArrowRight, 0.2).is_empty());
+        assert_eq!(tap(&mut app, KeyCode::KeyP, 0.2), vec![4]);
+        assert!(tap(&mut app, KeyCode::ArrowDown, 0.2).is_empty());
+        assert!(tap(&mut app, KeyCode::ArrowRight, 1.2).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyP, 0.2).is_empty());
+    }
+
+    #[test]
+    fn input_nested_temporal_patterns() {
+        let mut app = input_test_app(
+            [
+                (
+                    UserButtonInput::sequence(
+                        [
+                            UserButtonInput::double_tap(
+                                UserButtonInput::KeyDown(KeyCode::KeyA),
+                                0.3,
+                            ),
+                            UserButtonInput::KeyDown(KeyCode::KeyB),
+                        ],
+                        0.5,
+                    ),
+                    1,
+                ),
+                (
+                    Chord::new(
+                        [UserButtonInput::KeyPressed(KeyCode::ShiftLeft)],
+                        UserButtonInput::double_tap(UserButtonInput::KeyDown(KeyCode::KeyD), 0.3),
+                    )
+                    .into(),
+                    2,
+                ),
+            ]
+            .into(),
+        );
+        let tap = |app: &mut App, key_code: KeyCode| {
+            press(app, key_code, ButtonState::Pressed);
+            let actions = read_actions(app);
+            press(app, key_code, ButtonState::Released);
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(0.1));
+            assert!(read_actions(app).is_empty());
+            actions
+        };
+
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert_eq!(tap(&mut app, KeyCode::KeyB), vec![1]);
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyB).is_empty());
+
+        assert!(tap(&mut app, KeyCode::KeyD).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyD).is_empty());
+        press(&mut app, KeyCode::ShiftLeft, ButtonState::Pressed);
+        assert!(tap(&mut app, KeyCode::KeyD).is_empty());
+        assert_eq!(tap(&mut app, KeyCode::KeyD), vec![2]);
+    }
+
+    #[test]
+    fn input_sequence_restarts_on_other_input() {
+        let mut app = input_test_app(
+            [(
+                UserButtonInput::sequence(
+                    [
+                        UserButtonInput::KeyDown(KeyCode::KeyA),
+                        UserButtonInput::KeyDown(KeyCode::KeyB),
+                    ],
+                    0.5,
+                ),
+                1,
+            )]
+            .into(),
+        );
+        let tap = |app: &mut App, key_code: KeyCode| {
+            press(app, key_code, ButtonState::Pressed);
+            let mut actions = read_actions(app);
+            press(app, key_code, ButtonState::Released);
+            actions.extend(read_actions(app));
+            actions
+        };
+
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyX).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyB).is_empty());
+
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert!(tap(&mut app, KeyCode::KeyA).is_empty());
+        assert_eq!(tap(&mut app, KeyCode::KeyB), vec![1]);
+    }
+
+    #[test]
+    fn input_rebinding() {
+        let mut app = input_test_app(
+            [
+                (UserButtonInput::KeyDown(KeyCode::KeyA), 1),
+                (UserButtonInput::KeyPressed(KeyCode::KeyB), 2),
+            ]
+            .into(),
+        );
+        let tap = |app: &mut App, key_code: KeyCode| {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                press(app, key_code, state);
+                app.update();
+            }
+        };
+        let rebind_events = |app: &mut App| {
+            app.world
+                .resource_mut::<Events<RebindEvent<i32>>>()
+                .drain()
+                .collect::<Vec<_>>()
+        };
+
+        app.world
+            .send_event(RebindRequest::new(1).replacing(UserButtonInput::KeyDown(KeyCode::KeyA)));
+        app.update();
+        tap(&mut app, KeyCode::KeyB);
+        let events = rebind_events(&mut app);
+        assert!(matches!(
+            events.as_slice(),
+            [RebindEvent::Conflict { action: 1, conflicts, .. }] if conflicts == &vec![2]
+        ));
+
+        app.world
+            .send_event(RebindRequest::new(1).replacing(UserButtonInput::KeyDown(KeyCode::KeyA)));
+        app.update();
+        tap(&mut app, KeyCode::KeyC);
+        let events = rebind_events(&mut app);
+        assert!(matches!(
+            events.as_slice(),
+            [RebindEvent::Bound {
+                action: 1,
+                input: UserButtonInput::KeyDown(KeyCode::KeyC)
+            }]
+        ));
+        let mapping = app.world.resource::<InputMapping<i32>>();
+        assert!(mapping
+            .find_conflicts(&UserButtonInput::KeyUp(KeyCode::KeyA))
+            .is_empty());
+        assert_eq!(
+            mapping.find_conflicts(&UserButtonInput::KeyUp(KeyCode::KeyC)),
+            vec![&1]
+        );
+    }
+
+    #[test]
+    fn input_rebinding_changes_the_mapping_only_when_bound() {
+        #[derive(Default, Resource)]
+        struct Changes(u32);
+
+        let mut app = input_test_app([(UserButtonInput::KeyDown(KeyCode::KeyA), 1)].into());
+        app.init_resource::<Changes>().add_systems(
+            Last,
+            |mapping: Res<InputMapping<i32>>, mut changes: ResMut<Changes>| {
+                if mapping.is_changed() && !mapping.is_added() {
+                    changes.0 += 1;
+                }
+            },
+        );
+        app.world.send_event(RebindRequest::new(1));
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<Changes>().0, 0);
+
+        press(&mut app, KeyCode::KeyC, ButtonState::Pressed);
+        app.update();
+        assert_eq!(app.world.resource::<Changes>().0, 1);
+    }
+
+    #[test]
+    fn input_rebinding_without_mapping_is_cancelled() {
+        let mut app = input_test_app([(UserButtonInput::KeyDown(KeyCode::KeyA), 1)].into());
+        app.world.remove_resource::<InputMapping<i32>>();
+        app.world.send_event(RebindRequest::new(1));
+        app.update();
+        app.update();
+        assert!(app.world.resource::<PendingRebind<i32>>().request.is_none());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn input_mapping_file_is_only_saved_on_changes() {
+        let path = std::env::temp_dir().join(format!(
+            "some_bevy_tools_input_mapping_{}.ron",
+            std::process::id()
+        ));
+        let mapping = InputMapping::<i32>::from([(UserButtonInput::KeyDown(KeyCode::KeyB), 2)]);
+        let ron = format!("// saved by the user\n{}", mapping.to_ron().unwrap());
+        std::fs::write(&path, &ron).unwrap();
+
+        let mut app = input_test_app([(UserButtonInput::KeyDown(KeyCode::KeyA), 1)].into());
+        app.add_plugins(InputMappingFilePlugin::<i32>::new(&path));
+        for _ in 0..3 {
+            app.update();
+        }
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let mapped = app
+            .world
+            .resource::<InputMapping<i32>>()
+            .get_mappings_as_slice()
+            == mapping.get_mappings_as_slice();
+
+        app.world.send_event(RebindRequest::new(2));
+        app.update();
+        press(&mut app, KeyCode::KeyC, ButtonState::Pressed);
+        app.update();
+        let rebound = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(mapped);
+        assert_eq!(saved, ron);
+        assert!(rebound.contains("KeyC"), "{rebound}");
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn input_mapping_ron_roundtrip() {
+        let mapping: InputMapping<i32> = (
+            [
+                (UserButtonInput::KeyDown(KeyCode::KeyA), 1),
+                (
+                    Chord::new(
+                        [UserButtonInput::KeyPressed(KeyCode::ControlLeft)],
+                        UserButtonInput::KeyDown(KeyCode::KeyS),
+                    )
+                    .into(),
+                    2,
+                ),
+            ],
+            [(
+                SliderMappingType::GamepadStick(GamepadSide::Left, 0.2),
+                3,
+                1.0,
+            )],
+        )
+            .into();
+        let ron = mapping.to_ron().unwrap();
+        let loaded = InputMapping::<i32>::from_ron(&ron).unwrap();
+        assert!(loaded.get_mappings_as_slice() == mapping.get_mappings_as_slice());
+        assert!(
+            loaded.get_directional_mappings_as_slice()
+                == mapping.get_directional_mappings_as_slice()
+        );
+    }
+
+    #[test]
+    fn input_player_mappings() {
+        let mut app = input_test_app([(UserButtonInput::KeyDown(KeyCode::KeyW), 1)].into());
+        let player_1 = app
+            .world
+            .spawn(
+                InputMapping::<i32>::from([(UserButtonInput::KeyDown(KeyCode::KeyW), 1)])
+                    .with_device(InputDevice::KeyboardMouse),
+            )
+            .id();
+        let player_2 = app
+            .world
+            .spawn(
+                InputMapping::<i32>::from([
+                    (UserButtonInput::KeyDown(KeyCode::KeyW), 2),
+                    (
+                        UserButtonInput::GamepadButtonDown(GamepadButtonType::South),
+                        2,
+                    ),
+                ])
+                .with_gamepad(Gamepad::new(0)),
+            )
+            .id();
+        app.world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                Gamepad::new(0),
+                GamepadConnection::Connected(GamepadInfo {
+                    name: "Test".into(),
+                }),
+            )));
+        app.update();
+
+        press(&mut app, KeyCode::KeyW, ButtonState::Pressed);
+        app.update();
+        let mut events: Vec<(i32, Option<Entity>)> = app
+            .world
+            .resource_mut::<Events<ActionEvent<i32>>>()
+            .drain()
+            .map(|event| (event.action, event.player))
+            .collect();
+        events.sort();
+        assert_eq!(events, vec![(1, None), (1, Some(player_1))]);
+
+        app.world
+            .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                Gamepad::new(0),
+                GamepadButtonType::South,
+                1.0,
+            )));
+        app.update();
+        let events: Vec<(i32, Option<Entity>)> = app
+            .world
+            .resource_mut::<Events<ActionEvent<i32>>>()
+            .drain()
+            .map(|event| (event.action, event.player))
+            .collect();
+        assert_eq!(events, vec![(2, Some(player_2))]);
+    }
+
+    #[test]
+    fn input_action_state() {
+        #[derive(Resource, Default)]
+        struct Counter(usize);
+
+        let mut app = input_test_app([(UserButtonInput::KeyPressed(KeyCode::KeyW), 1)].into());
+        app.init_resource::<Counter>().add_systems(
+            Update,
+            (|mut counter: ResMut<Counter>| counter.0 += 1)
+                .run_if(action_just_pressed(1))
+                .after(InputMappingSystem),
+        );
+        let advance = |app: &mut App| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(0.5));
+            app.update();
+        };
+
+        press(&mut app, KeyCode::KeyW, ButtonState::Pressed);
+        advance(&mut app);
+        let state = app.world.resource::<ActionState<i32>>();
+        assert!(state.pressed(&1) && state.just_pressed(&1));
+        assert_eq!(state.held_duration(&1), 0.0);
+
+        advance(&mut app);
+        advance(&mut app);
+        let state = app.world.resource::<ActionState<i32>>();
+        assert!(state.pressed(&1) && !state.just_pressed(&1));
+        assert_eq!(state.held_duration(&1), 1.0);
+
+        press(&mut app, KeyCode::KeyW, ButtonState::Released);
+        advance(&mut app);
+        let state = app.world.resource::<ActionState<i32>>();
+        assert!(!state.pressed(&1) && state.just_released(&1));
+        assert_eq!(app.world.resource::<Counter>().0, 1);
+
+        press(&mut app, KeyCode::KeyW, ButtonState::Pressed);
+        advance(&mut app);
+        assert!(app.world.resource::<ActionState<i32>>().just_pressed(&1));
+        assert_eq!(app.world.resource::<Counter>().0, 2);
+    }
+
+    #[test]
+    fn input_virtual_dpad() {
+        let mut app = input_test_app(
+            (
+                [],
+                [(SliderMappingType::VirtualDpad(VirtualDpad::wasd()), 1, 2.0)],
+            )
+                .into(),
+        );
+        for key_code in [KeyCode::KeyW, KeyCode::KeyD] {
+            press(&mut app, key_code, ButtonState::Pressed);
+        }
+        app.update();
+
+        let expected = Vec2::new(1.0, 1.0).normalize() * 2.0;
+        let sliders: Vec<Vec2> = app
+            .world
+            .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+            .drain()
+            .map(|event| Vec2::new(event.x, event.y))
+            .collect();
+        assert_eq!(sliders, vec![expected]);
+        assert_eq!(app.world.resource::<ActionState<i32>>().axis(&1), expected);
+    }
+
+    #[test]
+    fn input_contexts() {
+        let mut app = input_test_app(
+            InputMapping::from([(UserButtonInput::KeyDown(KeyCode::Escape), 1)])
+                .with_context("menu"),
+        );
+        app.add_plugins(InputMappingPlugin::<u8>::default())
+            .insert_resource(InputMapping::<u8>::from([
+                (UserButtonInput::KeyDown(KeyCode::Escape), 2),
+                (UserButtonInput::KeyDown(KeyCode::KeyW), 3),
+            ]));
+        let tap = |app: &mut App, key_code: KeyCode| {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                press(app, key_code, state);
+                app.update();
+            }
+            let menu_actions: Vec<i32> = app
+                .world
+                .resource_mut::<Events<ActionEvent<i32>>>()
+                .drain()
+                .map(|event| event.action)
+                .collect();
+            let game_actions: Vec<u8> = app
+                .world
+                .resource_mut::<Events<ActionEvent<u8>>>()
+                .drain()
+                .map(|event| event.action)
+                .collect();
+            (menu_actions, game_actions)
+        };
+
+        assert_eq!(tap(&mut app, KeyCode::Escape), (vec![], vec![2]));
+
+        app.world
+            .resource_mut::<InputContexts>()
+            .push(InputContext::new("menu", 10));
+        assert_eq!(tap(&mut app, KeyCode::Escape), (vec![1], vec![]));
+        assert_eq!(tap(&mut app, KeyCode::KeyW), (vec![], vec![3]));
+
+        app.world
+            .resource_mut::<InputContexts>()
+            .push(InputContext::new("menu", 10).blocking());
+        assert_eq!(tap(&mut app, KeyCode::KeyW), (vec![], vec![]));
+
+        app.world.resource_mut::<InputContexts>().pop();
+        assert_eq!(tap(&mut app, KeyCode::Escape), (vec![], vec![2]));
+    }
+
+    #[test]
+    fn input_contexts_claim_sliders() {
+        let mut app = input_test_app(
+            InputMapping::from(([], [(SliderMappingType::MouseMove(0.0), 1, 1.0)]))
+                .with_context("menu"),
+        );
+        app.add_plugins(InputMappingPlugin::<u8>::default())
+            .insert_resource(InputMapping::<u8>::from((
+                [],
+                [(SliderMappingType::MouseMove(0.0), 2, 1.0)],
+            )));
+        let move_mouse = |app: &mut App| {
+            app.world.send_event(bevy::input::mouse::MouseMotion {
+                delta: Vec2::new(1.0, 0.0),
+            });
+            app.update();
+            let menu_sliders = app
+                .world
+                .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+                .drain()
+                .count();
+            let game_sliders = app
+                .world
+                .resource_mut::<Events<DirectionSliderEvent<u8>>>()
+                .drain()
+                .count();
+            (menu_sliders, game_sliders)
+        };
+
+        assert_eq!(move_mouse(&mut app), (0, 1));
+        app.world
+            .resource_mut::<InputContexts>()
+            .push(InputContext::new("menu", 10));
+        assert_eq!(move_mouse(&mut app), (1, 0));
+        app.world.resource_mut::<InputContexts>().pop();
+        assert_eq!(move_mouse(&mut app), (0, 1));
+    }
+
+    #[test]
+    fn input_mouse_sliders() {
+        let mapping: InputMapping<i32> = (
+            [],
+            [
+                (SliderMappingType::MouseMove(2.0), 1, 1.0),
+                (SliderMappingType::Mouse(MouseSlider::new(2.0)), 2, 1.0),
+                (
+                    SliderMappingType::Mouse(
+                        MouseSlider::default().with_smoothing(0.5).inverted_y(),
+                    ),
+                    3,
+                    1.0,
+                ),
+                (
+                    SliderMappingType::CursorPosition(CursorSpace::Window),
+                    4,
+                    1.0,
+                ),
+            ],
+        )
+            .into();
+        let mut app = input_test_app(mapping);
+        let move_mouse = |app: &mut App, deltas: &[Vec2]| {
+            for delta in deltas {
+                app.world.send_event(mouse::MouseMotion { delta: *delta });
+            }
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs(1) / 60);
+            app.update();
+            let mut values: Vec<(i32, Vec2)> = app
+                .world
+                .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+                .drain()
+                .map(|event| (event.action, Vec2::new(event.x, event.y)))
+                .collect();
+            values.sort_by_key(|(action, _)| *action);
+            values
+        };
+        let assert_values = |values: Vec<(i32, Vec2)>, expected: Vec<(i32, Vec2)>| {
+            assert_eq!(values.len(), expected.len(), "{values:?} != {expected:?}");
+            for ((action, value), (expected_action, expected_value)) in values.iter().zip(expected)
+            {
+                assert_eq!(*action, expected_action);
+                assert!(
+                    value.abs_diff_eq(expected_value, 0.001),
+                    "{value} != {expected_value}"
+                );
+            }
+        };
+
+        assert_values(
+            move_mouse(&mut app, &[Vec2::new(1.0, 0.0), Vec2::new(2.0, 2.0)]),
+            vec![
+                (1, Vec2::new(2.0, 2.0)),
+                (2, Vec2::new(3.0, 2.0)),
+                (3, Vec2::new(1.5, -1.0)),
+            ],
+        );
+        assert_values(move_mouse(&mut app, &[]), vec![(3, Vec2::new(0.75, -0.5))]);
+
+        let mut window = Window::default();
+        window.set_cursor_position(Some(Vec2::new(100.0, 50.0)));
+        app.world.spawn((window, PrimaryWindow));
+        assert_values(
+            move_mouse(&mut app, &[Vec2::new(1.0, 0.0)]),
+            vec![(3, Vec2::new(0.875, -0.25)), (4, Vec2::new(100.0, 50.0))],
+        );
+    }
+
+    #[test]
+    fn input_mouse_smoothing_is_frame_rate_independent() {
+        let decay = |frames_per_second: u32| {
+            let mapping: InputMapping<i32> = (
+                [],
+                [(
+                    SliderMappingType::Mouse(MouseSlider::default().with_smoothing(0.9)),
+                    1,
+                    1.0,
+                )],
+            )
+                .into();
+            let mut app = input_test_app(mapping);
+            let mut values = Vec::new();
+            for frame in 0..=frames_per_second / 4 {
+                if frame == 0 {
+                    app.world.send_event(mouse::MouseMotion {
+                        delta: Vec2::new(100.0, 0.0),
+                    });
+                }
+                app.world
+                    .resource_mut::<Time>()
+                    .advance_by(Duration::from_secs(1) / frames_per_second);
+                app.update();
+                values.extend(
+                    app.world
+                        .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+                        .drain()
+                        .map(|event| event.x),
+                );
+            }
+            values.last().unwrap() / values.first().unwrap()
+        };
+        let expected = 0.9_f32.powf(15.0);
+        assert!((decay(40) - expected).abs() < 0.001, "{}", decay(40));
+        assert!((decay(120) - expected).abs() < 0.001, "{}", decay(120));
+    }
+
+    #[test]
+    fn input_mouse_smoothing_without_frame_time() {
+        let mut app = input_test_app(
+            (
+                [],
+                [(
+                    SliderMappingType::Mouse(MouseSlider::default().with_smoothing(0.9)),
+                    1,
+                    1.0,
+                )],
+            )
+                .into(),
+        );
+        app.world.send_event(mouse::MouseMotion {
+            delta: Vec2::new(100.0, 0.0),
+        });
+        app.update();
+        let values: Vec<f32> = app
+            .world
+            .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+            .drain()
+            .map(|event| event.x)
+            .collect();
+        assert_eq!(values, vec![100.0]);
+    }
+
+    #[test]
+    fn input_recording_and_replay() {
+        let mut app = input_test_app(InputMapping::from([(
+            UserButtonInput::KeyDown(KeyCode::KeyA),
+            1,
+        )]));
+        app.init_resource::<InputRecorder<i32>>();
+        app.update();
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            press(&mut app, KeyCode::KeyA, state);
+            app.update();
+        }
+        let recording = app
+            .world
+            .remove_resource::<InputRecorder<i32>>()
+            .unwrap()
+            .into_recording();
+        assert_eq!(
+            recording.frames,
+            vec![RecordedFrame {
+                frame: 1,
+                actions: vec![(1, 1.0)],
+                sliders: vec![],
+            }]
+        );
+        #[cfg(feature = "serialize")]
+        assert_eq!(
+            InputRecording::<i32>::from_ron(&recording.to_ron().unwrap()).unwrap(),
+            recording
+        );
+
+        let mut app = input_test_app(InputMapping::from([(
+            UserButtonInput::KeyDown(KeyCode::KeyA),
+            3,
+        )]));
+        app.insert_resource(InputReplay::new(recording));
+        press(&mut app, KeyCode::KeyA, ButtonState::Pressed);
+        assert_eq!(read_actions(&mut app), Vec::<i32>::new());
+        assert_eq!(read_actions(&mut app), vec![1]);
+        assert!(app.world.resource::<ActionState<i32>>().just_pressed(&1));
+        assert!(app.world.resource::<InputReplay<i32>>().is_finished());
+    }
+
+    #[test]
+    fn input_touch() {
+        use bevy::input::touch::{TouchInput, TouchPhase};
+
+        let mapping: InputMapping<i32> = (
+            [
+                (UserButtonInput::TouchTap, 1),
+                (UserButtonInput::touch_long_press(0.5), 2),
+                (UserButtonInput::TouchSwipe(SwipeDirection::Left), 3),
+            ],
+            [
+                (SliderMappingType::TouchPinch, 4, 1.0),
+                (
+                    SliderMappingType::TouchJoystick(TouchJoystick::new(
+                        Rect::new(0.0, 0.0, 100.0, 100.0),
+                        20.0,
+                    )),
+                    5,
+                    1.0,
+                ),
+            ],
+        )
+            .into();
+        let mut app = input_test_app(mapping);
+        let touch = |app: &mut App, id: u64, phase: TouchPhase, x: f32, y: f32| {
+            app.world.send_event(TouchInput {
+                phase,
+                position: Vec2::new(x, y),
+                window: Entity::PLACEHOLDER,
+                force: None,
+                id,
+            });
+        };
+        let sliders = |app: &mut App| {
+            let mut values: Vec<(i32, Vec2)> = app
+                .world
+                .resource_mut::<Events<DirectionSliderEvent<i32>>>()
+                .drain()
+                .map(|event| (event.action, Vec2::new(event.x, event.y)))
+                .collect();
+            values.sort_by_key(|(action, _)| *action);
+            values
+        };
+        let wait = |app: &mut App, seconds: f32| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(seconds));
+        };
+
+        touch(&mut app, 0, TouchPhase::Started, 500.0, 500.0);
+        assert_eq!(read_actions(&mut app), Vec::<i32>::new());
+        touch(&mut app, 0, TouchPhase::Ended, 505.0, 500.0);
+        assert_eq!(read_actions(&mut app), vec![1]);
+
+        touch(&mut app, 0, TouchPhase::Started, 500.0, 500.0);
+        assert_eq!(read_actions(&mut app), Vec::<i32>::new());
+        wait(&mut app, 0.6);
+        assert_eq!(read_actions(&mut app), vec![2]);
+        wait(&mut app, 0.1);
+        assert_eq!(read_actions(&mut app), Vec::<i32>::new());
+        touch(&mut app, 0, TouchPhase::Moved, 400.0, 500.0);
+        touch(&mut app, 0, TouchPhase::Ended, 400.0, 500.0);
+        assert_eq!(read_actions(&mut app), vec![3]);
+        sliders(&mut app);
+
+        touch(&mut app, 1, TouchPhase::Started, 50.0, 50.0);
+        touch(&mut app, 2, TouchPhase::Started, 300.0, 50.0);
+        app.update();
+        assert_eq!(sliders(&mut app), vec![(5, Vec2::ZERO)]);
+        touch(&mut app, 1, TouchPhase::Moved, 60.0, 40.0);
+        touch(&mut app, 2, TouchPhase::Moved, 320.0, 40.0);
+        app.update();
+        assert_eq!(
+            sliders(&mut app),
+            vec![(4, Vec2::new(10.0, 0.0)), (5, Vec2::new(0.5, 0.5))]
+        );
+    }
+
+    #[test]
+    fn input_analog_strength() {
+        let gamepad = Gamepad::new(0);
+        let mapping: InputMapping<i32> = [
+            (UserButtonInput::gamepad_trigger(GamepadSide::Right, 0.5), 1),
+            (UserButtonInput::gamepad_trigger(GamepadSide::Left, 0.1), 1),
+        ]
+        .into();
+        let mut app = input_test_app(mapping.with_combination(ActionCombination::SumClamped));
+        app.world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                gamepad,
+                GamepadConnection::Connected(GamepadInfo {
+                    name: "Test".into(),
+                }),
+            )));
+        app.update();
+        let trigger = |app: &mut App, button_type: GamepadButtonType, value: f32| {
+            app.world
+                .resource_mut::<Axis<GamepadButton>>()
+                .set(GamepadButton::new(gamepad, button_type), value);
+            app.update();
+            app.world
+                .resource_mut::<Events<ActionEvent<i32>>>()
+                .drain()
+                .map(|event| (event.action, event.strength))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            trigger(&mut app, GamepadButtonType::RightTrigger2, 0.3),
+            vec![]
+        );
+        assert_eq!(
+            trigger(&mut app, GamepadButtonType::RightTrigger2, 0.6),
+            vec![(1, 0.6)]
+        );
+        let actions = trigger(&mut app, GamepadButtonType::LeftTrigger2, 0.3);
+        assert_eq!(actions.len(), 1);
+        assert!((actions[0].1 - 0.9).abs() < 0.0001);
+
+        app.world
+            .resource_mut::<InputMapping<i32>>()
+            .set_combination(ActionCombination::Max);
+        assert_eq!(
+            trigger(&mut app, GamepadButtonType::LeftTrigger2, 0.2),
+            vec![(1, 0.6)]
+        );
+        assert_eq!(app.world.resource::<ActionState<i32>>().strength(&1), 0.6);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn input_mapping_ron_defaults() {
+        let mapping =
+            InputMapping::<i32>::from_ron("(button_mapping: [], slider_mapping: [])").unwrap();
+        assert_eq!(mapping.get_device(), InputDevice::All);
+        assert_eq!(mapping.get_context(), None);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn input_mapping_errors_contain_the_line() {
+        let err = InputMapping::<i32>::from_ron("(\n    button_mapping: [\n        Oops,\n")
+            .map_err(InputMappingFileError::Deserialize)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("line 3"), "{err}");
+    }
+
+    #[cfg(feature = "input_asset")]
+    #[test]
+    fn input_mapping_asset_replaces_resource() {
+        let mut app = App::new();
+        app.add_plugins((
+            bevy::core::TaskPoolPlugin::default(),
+            bevy::asset::AssetPlugin::default(),
+            InputMappingAssetPlugin::<i32>::new("missing.input.ron"),
+        ))
+        .insert_resource(InputMapping::<i32>::from([(
+            UserButtonInput::KeyDown(KeyCode::KeyA),
+            1,
+        )]));
+        app.update();
+        let id = app.world.resource::<InputMappingHandle<i32>>().0.id();
+        let loaded: InputMapping<i32> = [(UserButtonInput::KeyDown(KeyCode::KeyB), 2)].into();
+        app.world
+            .resource_mut::<Assets<InputMapping<i32>>>()
+            .insert(id, loaded);
+        app.world
+            .send_event(AssetEvent::LoadedWithDependencies { id });
+        app.update();
+        assert!(
+            app.world
+                .resource::<InputMapping<i32>>()
+                .get_mappings_as_slice()
+                == [(UserButtonInput::KeyDown(KeyCode::KeyB), 2).into()]
+        );
+
+        let reloaded: InputMapping<i32> = [(UserButtonInput::KeyDown(KeyCode::KeyC), 3)].into();
+        app.world
+            .resource_mut::<Assets<InputMapping<i32>>>()
+            .insert(id, reloaded);
+        app.update();
+        app.update();
+        assert!(
+            app.world
+                .resource::<InputMapping<i32>>()
+                .get_mappings_as_slice()
+                == [(UserButtonInput::KeyDown(KeyCode::KeyC), 3).into()]
+        );
+    }
+
+    #[cfg(feature = "input_asset")]
+    #[test]
+    fn input_mapping_loader_errors_contain_the_line() {
+        let err =
+            ron::de::from_bytes::<InputMapping<i32>>(b"(\n    button_mapping: [\n        Oops,\n")
+                .map_err(InputMappingFileError::Deserialize)
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("line 3"), "{err}");
+    }
 }
@@ -10,8 +10,10 @@
 //! * Automatic despawn of components on a state change.
 //! * Range component which keeps its value between a min and a max value and writes events
 //!   if min or max was reached.  For example it can be used for health to detect death.
+//!   There is also a discrete version with integer values for things like ammo.
 //! * Simplified processing of events on collisions in rapier.
-//! * Mapping of user inputs to custom events. (currently only keyboard events are supported for now)
+//! * Mapping of keyboard, mouse, gamepad and touch inputs to custom events, with per player
+//!   mappings for local multiplayer.
 //! * Loading of assets on a loading state and storing them automatically in a resource using reflect.
 //! * Split screen support.
 //! * SBS support. It is basically a split screen which allows a sterioscopic view by using special
@@ -19,6 +21,21 @@
 //!
 //! Additionally, I try to document each module with at least one example. This should ensure that
 //! there are no accidential breaking changes.
+//!
+//! ## Features
+//! The default `all` feature enables everything except for rapier.  The features are:
+//!
+//! | Feature            | Description                                                    |
+//! | ------------------ | -------------------------------------------------------------- |
+//! | audio_loop         | Adds support for looping inside of audio files (usually music) |
+//! | loading            | Load assets into resources using reflect.                      |
+//! | split_screen       | Enables split screen support using two cameras.                |
+//! | sbs_3d             | Allow 3D output using SBS (side-by-side) rendering.            |
+//! | serialize          | Serde support for components like `Range`.                     |
+//! | input_cursor_world | Cursor position slider in world coordinates.                   |
+//! | input_asset        | Load input mappings from `.input.ron` assets with hot reload.  |
+//! | bevy_rapier2d      | Simplify collision events with rapier 2D.                      |
+//! | bevy_rapier3d      | Simplify collision events with rapier 3D.                      |
 
 #[cfg(feature = "audio_loop")]
 pub mod audio_loop;
@@ -29,6 +46,7 @@ pub mod collision_detection;
 pub mod collision_detection;
 pub mod controller_2d;
 pub mod despawn;
+pub mod discrete_range;
 pub mod health;
 pub mod input;
 #[cfg(feature = "loading")]
@@ -102,6 +102,35 @@ pub fn acceleration_controller(mut query: Query<(&mut Velocity, &Acceleration)>,
     }
 }
 
+/// System which starts the SimpleTopDownControllers with the Velocity drive from the
+/// current rapier Velocity.
+pub fn read_top_down_velocity(
+    mut query: Query<(
+        &mut crate::controller_2d::SimpleTopDownController,
+        &Velocity,
+    )>,
+) {
+    for (mut controller, velocity) in query.iter_mut() {
+        if controller.drive == crate::controller_2d::TopDownDrive::Velocity {
+            controller.velocity = velocity.linvel;
+        }
+    }
+}
+
+/// Applies the velocity of top down controllers which drive the rapier Velocity.
+pub fn top_down_velocity_controller(
+    mut query: Query<(
+        &crate::controller_2d::SimpleTopDownController,
+        &mut Velocity,
+    )>,
+) {
+    for (controller, mut velocity) in query.iter_mut() {
+        if controller.drive == crate::controller_2d::TopDownDrive::Velocity {
+            velocity.linvel = controller.velocity;
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Physics2DPluginConfiguration {
     pub no_rapier_plugin: bool,
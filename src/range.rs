@@ -5,23 +5,46 @@
 //! its stamina, or its mana.
 //!
 //! It will also automatically modify the range based on the change_per_second
-//! attribute to automatically fill up mana or heal.
+//! attribute to automatically fill up mana or heal.  The regeneration can be paused
+//! for a while after the value was decreased and it can follow a RegenerationCurve.
 //!
 //! When the range reaches a limit, an event will be emitted.  The events
 //! are StartRangeLimitReachedEvent and EndRangeLimitReachedEvent.  They can
-//! be used to trigger specific actions like dying.
+//! be used to trigger specific actions like dying.  The events are only sent once
+//! when the limit is reached and a LimitLeftEvent is sent when the value moves away
+//! from the limit again.
+//!
+//! Additional marks between the limits, like "health below 25%", can be defined
+//! with the RangeThresholds component.  A RangeThresholdCrossedEvent is sent when
+//! the value crosses one of them.
+//!
+//! Temporary buffs and debuffs of the limits and the change_per_second attribute can
+//! be added with the RangeModifiers component.  They are applied and expired by the
+//! RangePlugin.
+//!
+//! Ranges are reflectable and registered by the RangePlugin, so they can be
+//! stored in scenes and shown in inspectors.  For this, the type parameter must
+//! implement TypePath.  With the `serialize` feature, they can also be serialized
+//! with serde.  Only the configuration and the current value are stored, so no
+//! events are sent for a range which was just loaded.
+//!
+//! Every change of the value is reported with a RangeChangedEvent which contains
+//! the previous and the new value.  Changes done with the RangeWriter system param
+//! are reported immediately, all other changes are detected by the update_range system.
 //!
 //! # Examples
 //! ```rust
 //! use bevy::prelude::*;
 //! use some_bevy_tools::range::{Range, RangePlugin};
 //!
-//! #[derive(Default)]
+//! #[derive(Default, TypePath)]
 //! struct Health;
 //!
 //! App::new()
 //!     .add_plugins(RangePlugin::<Health>::default());
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
 
 /// Quantize `value` to the nearest multiple of `step`.
 ///
@@ -52,6 +75,92 @@ pub enum ModifyRangeResult {
     EndLimitReached { high_limit: f32, value: f32 },
 }
 
+/// One of the two limits of a range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum RangeLimit {
+    Start,
+    End,
+}
+
+/// Defines how fast a range regenerates over time.
+///
+/// The curve returns a factor for the change_per_second attribute based on the
+/// seconds since the regeneration started.
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum RegenerationCurve {
+    /// Always regenerate with the full change_per_second.
+    #[default]
+    Linear,
+
+    /// Start slowly and reach the full change_per_second after the duration in seconds.
+    EaseIn { duration: f32 },
+
+    /// Approach the full change_per_second exponentially.  A higher rate reaches the
+    /// full speed faster.  A rate which is not positive regenerates like Linear.
+    Exponential { rate: f32 },
+
+    /// Linear interpolation between keyframes of seconds and factor.  The keyframes
+    /// must be sorted by the seconds.  Before the first and after the last keyframe,
+    /// the factor of the first or last keyframe is used.
+    Keyframes(Vec<(f32, f32)>),
+}
+
+impl RegenerationCurve {
+    /// Get the factor for the change_per_second after the given seconds of regeneration.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use some_bevy_tools::range::RegenerationCurve;
+    ///
+    /// assert_eq!(RegenerationCurve::Linear.sample(0.0), 1.0);
+    /// assert_eq!(RegenerationCurve::EaseIn { duration: 2.0 }.sample(1.0), 0.25);
+    /// assert_eq!(RegenerationCurve::EaseIn { duration: 2.0 }.sample(3.0), 1.0);
+    ///
+    /// let keyframes = RegenerationCurve::Keyframes(vec![(0.0, 0.0), (1.0, 2.0), (2.0, 1.0)]);
+    /// assert_eq!(keyframes.sample(0.5), 1.0);
+    /// assert_eq!(keyframes.sample(1.5), 1.5);
+    /// assert_eq!(keyframes.sample(5.0), 1.0);
+    /// ```
+    pub fn sample(&self, time: f32) -> f32 {
+        match self {
+            RegenerationCurve::Linear => 1.0,
+            RegenerationCurve::EaseIn { duration } => {
+                if *duration <= 0.0 {
+                    1.0
+                } else {
+                    (time / duration).clamp(0.0, 1.0).powi(2)
+                }
+            }
+            RegenerationCurve::Exponential { rate } => {
+                if *rate > 0.0 && rate.is_finite() {
+                    1.0 - (-rate * time).exp()
+                } else {
+                    1.0
+                }
+            }
+            RegenerationCurve::Keyframes(keyframes) => {
+                let Some(&(first_time, first_factor)) = keyframes.first() else {
+                    return 1.0;
+                };
+                if time <= first_time {
+                    return first_factor;
+                }
+                for window in keyframes.windows(2) {
+                    let (start_time, start_factor) = window[0];
+                    let (end_time, end_factor) = window[1];
+                    if time <= end_time {
+                        let progress = (time - start_time) / (end_time - start_time);
+                        return start_factor + (end_factor - start_factor) * progress;
+                    }
+                }
+                keyframes.last().map(|(_, factor)| *factor).unwrap_or(1.0)
+            }
+        }
+    }
+}
+
 /// A range that contains a floating point between two values.
 ///
 /// It uses a type parameter to allow for different
@@ -60,7 +169,9 @@ pub enum ModifyRangeResult {
 /// an entity or stamina.
 ///
 /// The attribute change_per_second can be used to automatically modify the
-/// current value of the range.
+/// current value of the range.  With a regeneration delay, the automatic modification
+/// pauses after the value was decreased from outside and the regeneration curve
+/// defines how fast the full change_per_second is reached again.
 ///
 /// # Examples
 /// ```rust
@@ -78,13 +189,32 @@ pub enum ModifyRangeResult {
 /// range.set(6.0);
 /// assert_eq!(range.get(), 6.0);
 /// ```
-#[derive(Clone, Debug, Component, PartialEq)]
+#[derive(Clone, Debug, Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(bound = "", default)
+)]
 pub struct Range<T> {
     start: f32,
     end: f32,
     current: f32,
     quantize: f32,
     change_per_second: f32,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    reported: f32,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    limit: Option<RangeLimit>,
+    regeneration_delay: f32,
+    regeneration_curve: RegenerationCurve,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    time_since_decrease: Option<f32>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -99,21 +229,26 @@ impl<T> Range<T> {
             current: end,
             quantize: 1.0,
             change_per_second: 0.0,
+            reported: end,
+            limit: Some(RangeLimit::End),
+            regeneration_delay: 0.0,
+            regeneration_curve: RegenerationCurve::Linear,
+            time_since_decrease: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
     /// Create a new range with the current value set to the start value.
     pub fn with_start(self, start: f32) -> Range<T> {
-        Range { start, ..self }
+        Range { start, ..self }.mark_reported()
     }
     /// Create a new range with the current value set to the end value.
     pub fn with_end(self, end: f32) -> Range<T> {
-        Range { end, ..self }
+        Range { end, ..self }.mark_reported()
     }
     /// Create a new range with the current value set to the current value.
     pub fn with_current(self, current: f32) -> Range<T> {
-        Range { current, ..self }
+        Range { current, ..self }.mark_reported()
     }
     /// Create a new range with the quantize value set to the quantize value.
     pub fn with_quantize(self, quantize: f32) -> Range<T> {
@@ -126,6 +261,21 @@ impl<T> Range<T> {
             ..self
         }
     }
+    /// Create a new range which pauses the regeneration for the given seconds after the
+    /// value was decreased from outside.
+    pub fn with_regeneration_delay(self, regeneration_delay: f32) -> Range<T> {
+        Range {
+            regeneration_delay,
+            ..self
+        }
+    }
+    /// Create a new range with the given regeneration curve.
+    pub fn with_regeneration_curve(self, regeneration_curve: RegenerationCurve) -> Range<T> {
+        Range {
+            regeneration_curve,
+            ..self
+        }
+    }
 
     /// Set the quantize value.
     pub fn set_quantize(&mut self, quantize: f32) {
@@ -151,6 +301,43 @@ impl<T> Range<T> {
     pub fn get_change_per_second(&self) -> f32 {
         self.change_per_second
     }
+    /// Set the regeneration delay in seconds.
+    pub fn set_regeneration_delay(&mut self, regeneration_delay: f32) {
+        self.regeneration_delay = regeneration_delay
+    }
+    /// Get the regeneration delay in seconds.
+    pub fn get_regeneration_delay(&self) -> f32 {
+        self.regeneration_delay
+    }
+    /// Set the regeneration curve.
+    pub fn set_regeneration_curve(&mut self, regeneration_curve: RegenerationCurve) {
+        self.regeneration_curve = regeneration_curve
+    }
+    /// Get the regeneration curve.
+    pub fn get_regeneration_curve(&self) -> &RegenerationCurve {
+        &self.regeneration_curve
+    }
+
+    /// Get the factor for the change_per_second which is currently used by the regeneration.
+    ///
+    /// It is 0 during the regeneration delay and follows the regeneration curve afterwards.
+    pub fn get_regeneration_factor(&self) -> f32 {
+        match self.time_since_decrease {
+            Some(time) if time < self.regeneration_delay => 0.0,
+            Some(time) => self
+                .regeneration_curve
+                .sample(time - self.regeneration_delay),
+            None => self.regeneration_curve.sample(f32::INFINITY),
+        }
+    }
+
+    /// Set the start and the end value.
+    /// The current value is not modified, so it can be outside of the new limits
+    /// until it gets set or modified the next time.
+    pub fn set_limits(&mut self, start: f32, end: f32) {
+        self.start = start;
+        self.end = end;
+    }
 
     /// Set the current value.
     /// The result is Ok if the value is within the range,
@@ -192,6 +379,79 @@ impl<T> Range<T> {
     pub fn modify(&mut self, delta: f32) -> ModifyRangeResult {
         self.set(self.current + delta)
     }
+
+    /// Get the current value without quantization.
+    pub fn get_raw(&self) -> f32 {
+        self.current
+    }
+
+    /// Get the limit the range is currently at.
+    ///
+    /// This is the limit for which the last limit event was sent and it is None
+    /// if the value is between the limits.
+    pub fn get_limit(&self) -> Option<RangeLimit> {
+        self.limit
+    }
+
+    /// The limit at which the current value is, without looking at the last report.
+    fn detect_limit(&self) -> Option<RangeLimit> {
+        if self.current <= self.start {
+            Some(RangeLimit::Start)
+        } else if self.current >= self.end {
+            Some(RangeLimit::End)
+        } else {
+            None
+        }
+    }
+
+    /// Treat the current state as already reported so no events are sent for it.
+    fn mark_reported(mut self) -> Range<T> {
+        self.reset_reported();
+        self
+    }
+
+    /// Treat the current state as already reported so no events are sent for it.
+    fn reset_reported(&mut self) {
+        self.reported = self.current;
+        self.limit = self.detect_limit();
+    }
+
+    /// Returns the previous reported value if the value changed since the last report
+    /// and marks the current value as reported.
+    fn take_unreported_change(&mut self) -> Option<f32> {
+        let previous = self.reported;
+        self.reported = self.current;
+        if previous != self.current {
+            Some(previous)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the previous and the new limit if the range entered or left a limit
+    /// since the last report and remembers the new limit.
+    fn take_limit_transition(&mut self) -> Option<(Option<RangeLimit>, Option<RangeLimit>)> {
+        let previous = self.limit;
+        self.limit = self.detect_limit();
+        if previous != self.limit {
+            Some((previous, self.limit))
+        } else {
+            None
+        }
+    }
+}
+
+/// Ranges are equal if their configuration and their current value are equal.
+impl<T> PartialEq for Range<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start
+            && self.end == other.end
+            && self.current == other.current
+            && self.quantize == other.quantize
+            && self.change_per_second == other.change_per_second
+            && self.regeneration_delay == other.regeneration_delay
+            && self.regeneration_curve == other.regeneration_curve
+    }
 }
 
 impl<T> Default for Range<T> {
@@ -218,26 +478,725 @@ pub struct EndRangeLimitReachedEvent<T> {
     _phantom: std::marker::PhantomData<T>,
 }
 
-/// A system to update the range values based on their change_per_second attribute.
+/// An event which is sent when a range leaves a limit it was at before.
+///
+/// For example for health it could be used to revive an entity.
+#[derive(Debug, Event)]
+pub struct LimitLeftEvent<T> {
+    pub entity: Entity,
+    pub limit: RangeLimit,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+/// What caused a change of a range value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RangeChangeSource {
+    /// The value was changed by the change_per_second attribute.
+    Regeneration,
+
+    /// The value was changed from outside, for example by game code which
+    /// applies damage.
+    External,
+
+    /// The value was changed because the limits were changed by RangeModifiers.
+    Modifier,
+}
+
+/// An event which is sent whenever the value of a range changes.
+///
+/// This can be used to update HUD bars or to show damage numbers without
+/// polling the range every frame.
+#[derive(Debug, Event)]
+pub struct RangeChangedEvent<T> {
+    pub entity: Entity,
+    pub previous: f32,
+    pub current: f32,
+    pub delta: f32,
+    pub source: RangeChangeSource,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> RangeChangedEvent<T> {
+    pub fn new(entity: Entity, previous: f32, current: f32, source: RangeChangeSource) -> Self {
+        Self {
+            entity,
+            previous,
+            current,
+            delta: current - previous,
+            source,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Event writers for the limit events of a range.
+#[derive(SystemParam)]
+pub struct RangeLimitEventWriters<'w, T: Send + Sync + 'static> {
+    start_range_limit_reached_event_writer: EventWriter<'w, StartRangeLimitReachedEvent<T>>,
+    end_range_limit_reached_event_writer: EventWriter<'w, EndRangeLimitReachedEvent<T>>,
+    limit_left_event_writer: EventWriter<'w, LimitLeftEvent<T>>,
+}
+
+impl<'w, T: Send + Sync + 'static> RangeLimitEventWriters<'w, T> {
+    /// Send the events for a range which moved from the previous limit to the new limit.
+    ///
+    /// None means that the value was between the limits.
+    pub fn send_transition(
+        &mut self,
+        entity: Entity,
+        previous_limit: Option<RangeLimit>,
+        limit: Option<RangeLimit>,
+    ) {
+        if previous_limit == limit {
+            return;
+        }
+        if let Some(previous_limit) = previous_limit {
+            self.limit_left_event_writer.send(LimitLeftEvent {
+                entity,
+                limit: previous_limit,
+                _phantom: std::marker::PhantomData,
+            });
+        }
+        match limit {
+            Some(RangeLimit::Start) => {
+                self.start_range_limit_reached_event_writer
+                    .send(StartRangeLimitReachedEvent {
+                        entity,
+                        _phantom: std::marker::PhantomData,
+                    });
+            }
+            Some(RangeLimit::End) => {
+                self.end_range_limit_reached_event_writer
+                    .send(EndRangeLimitReachedEvent {
+                        entity,
+                        _phantom: std::marker::PhantomData,
+                    });
+            }
+            None => {}
+        }
+    }
+}
+
+/// All event writers which are required to report changes of a range.
+#[derive(SystemParam)]
+pub struct RangeEventWriters<'w, T: Send + Sync + 'static> {
+    range_changed_event_writer: EventWriter<'w, RangeChangedEvent<T>>,
+    limit_event_writers: RangeLimitEventWriters<'w, T>,
+}
+
+impl<'w, T: Send + Sync + 'static> RangeEventWriters<'w, T> {
+    /// Send the events for everything which changed on the range since the last report.
+    ///
+    /// Limit events are only sent when a limit is entered or left.
+    pub fn report(&mut self, entity: Entity, range: &mut Range<T>, source: RangeChangeSource) {
+        if let Some(previous) = range.take_unreported_change() {
+            if source == RangeChangeSource::External && range.current < previous {
+                range.time_since_decrease = Some(0.0);
+            }
+            self.range_changed_event_writer.send(RangeChangedEvent::new(
+                entity,
+                previous,
+                range.current,
+                source,
+            ));
+        }
+        if let Some((previous_limit, limit)) = range.take_limit_transition() {
+            self.limit_event_writers
+                .send_transition(entity, previous_limit, limit);
+        }
+    }
+}
+
+/// A system param to modify ranges which reports the changes immediately.
+///
+/// Changes done with this param send the RangeChangedEvent with the
+/// `External` source and the limit events in the same frame.
+///
+/// # Examples
+/// ```rust
+/// use bevy::prelude::*;
+/// use some_bevy_tools::range::RangeWriter;
+///
+/// struct Health;
+///
+/// fn apply_damage(mut ranges: RangeWriter<Health>, query: Query<Entity>) {
+///     for entity in query.iter() {
+///         ranges.modify(entity, -1.0);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct RangeWriter<'w, 's, T: Send + Sync + 'static> {
+    range_query: Query<'w, 's, &'static mut Range<T>>,
+    event_writers: RangeEventWriters<'w, T>,
+}
+
+impl<'w, 's, T: Send + Sync + 'static> RangeWriter<'w, 's, T> {
+    /// Get the range of the entity.
+    pub fn get(&self, entity: Entity) -> Option<&Range<T>> {
+        self.range_query.get(entity).ok()
+    }
+
+    /// Set the value of the range of the entity.
+    ///
+    /// Returns None if the entity has no range.
+    pub fn set(&mut self, entity: Entity, value: f32) -> Option<ModifyRangeResult> {
+        let mut range = self.range_query.get_mut(entity).ok()?;
+        let result = range.set(value);
+        self.event_writers
+            .report(entity, &mut range, RangeChangeSource::External);
+        Some(result)
+    }
+
+    /// Modify the value of the range of the entity by delta.
+    ///
+    /// Returns None if the entity has no range.
+    pub fn modify(&mut self, entity: Entity, delta: f32) -> Option<ModifyRangeResult> {
+        let current = self.range_query.get(entity).ok()?.current;
+        self.set(entity, current + delta)
+    }
+}
+
+/// Which attribute of a range is changed by a modifier.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RangeModifierTarget {
+    Start,
+    End,
+    ChangePerSecond,
+}
+
+/// How a modifier changes the attribute.
+///
+/// All flat values are added to the base value first and the sum of all percentages
+/// is applied on the result.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RangeModifierKind {
+    /// Add the value to the attribute.
+    Flat(f32),
+
+    /// Add a fraction of the attribute.  0.25 increases the attribute by 25%,
+    /// -0.25 decreases it by 25%.
+    Percentage(f32),
+}
+
+/// What happens if a modifier with the same name is added again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RangeModifierStacking {
+    /// Add the modifier again so both are active.
+    #[default]
+    Stack,
+
+    /// Add the modifier again but keep at most the given amount.  If the limit is
+    /// reached, the oldest modifier will be removed.  With a limit of 0, the modifier
+    /// is never added.
+    StackUpTo(usize),
+
+    /// Keep the existing modifier and only restart its duration.
+    Refresh,
+
+    /// Remove the existing modifier and add the new one.
+    Replace,
+}
+
+/// What happens with the current value if the limits of a range are changed by modifiers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RangeValuePolicy {
+    /// Keep the value at the same relative position between the limits.  A health
+    /// of 50 out of 100 becomes 75 out of 150.
+    #[default]
+    KeepProportion,
+
+    /// Keep the value and only clamp it to the new limits.
+    Clamp,
+}
+
+/// A single buff or debuff for a range.
+#[derive(Debug, Clone)]
+pub struct RangeModifier {
+    pub name: String,
+    pub target: RangeModifierTarget,
+    pub kind: RangeModifierKind,
+    pub stacking: RangeModifierStacking,
+    pub duration: Option<Timer>,
+}
+
+impl RangeModifier {
+    /// Create a modifier without duration which stacks.
+    pub fn new(
+        name: impl Into<String>,
+        target: RangeModifierTarget,
+        kind: RangeModifierKind,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            target,
+            kind,
+            stacking: RangeModifierStacking::default(),
+            duration: None,
+        }
+    }
+
+    /// Create a new modifier which expires after the given duration in seconds.
+    pub fn with_duration(self, duration: f32) -> Self {
+        Self {
+            duration: Some(Timer::from_seconds(duration, TimerMode::Once)),
+            ..self
+        }
+    }
+
+    /// Create a new modifier with the given stacking rule.
+    pub fn with_stacking(self, stacking: RangeModifierStacking) -> Self {
+        Self { stacking, ..self }
+    }
+}
+
+/// The values of a range before the modifiers were applied.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct RangeBaseValues {
+    start: f32,
+    end: f32,
+    change_per_second: f32,
+}
+
+/// A list of modifiers which are applied on the Range with the same type parameter.
+///
+/// The values of the range when the modifiers are applied the first time are used as
+/// base values.  If the limits or the change_per_second attribute should be changed
+/// while modifiers are active, `set_base` must be used because the changes on the
+/// range itself are overwritten by the modifiers.
+///
+/// # Examples
+/// ```rust
+/// use some_bevy_tools::range::*;
+///
+/// #[derive(Default)]
+/// struct Health;
+///
+/// let mut modifiers = RangeModifiers::<Health>::default();
+/// modifiers.add(
+///     RangeModifier::new(
+///         "potion",
+///         RangeModifierTarget::End,
+///         RangeModifierKind::Percentage(0.5),
+///     )
+///     .with_duration(10.0)
+///     .with_stacking(RangeModifierStacking::Refresh),
+/// );
+/// assert_eq!(modifiers.get_modifiers().len(), 1);
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct RangeModifiers<T> {
+    modifiers: Vec<RangeModifier>,
+    value_policy: RangeValuePolicy,
+    base: Option<RangeBaseValues>,
+    dirty: bool,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> Default for RangeModifiers<T> {
+    fn default() -> Self {
+        Self {
+            modifiers: Vec::new(),
+            value_policy: RangeValuePolicy::default(),
+            base: None,
+            dirty: true,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> RangeModifiers<T> {
+    /// Create new modifiers with the given value policy.
+    pub fn with_value_policy(self, value_policy: RangeValuePolicy) -> Self {
+        Self {
+            value_policy,
+            ..self
+        }
+    }
+
+    /// Add a modifier and respect its stacking rule.
+    pub fn add(&mut self, modifier: RangeModifier) {
+        let existing = self
+            .modifiers
+            .iter()
+            .position(|existing| existing.name == modifier.name);
+        match (modifier.stacking, existing) {
+            (RangeModifierStacking::StackUpTo(0), _) => return,
+            (RangeModifierStacking::Refresh, Some(index)) => {
+                if let Some(timer) = self.modifiers[index].duration.as_mut() {
+                    timer.reset();
+                }
+            }
+            (RangeModifierStacking::Replace, Some(index)) => {
+                self.modifiers.remove(index);
+                self.modifiers.push(modifier);
+            }
+            (RangeModifierStacking::StackUpTo(max), Some(index)) => {
+                let count = self
+                    .modifiers
+                    .iter()
+                    .filter(|existing| existing.name == modifier.name)
+                    .count();
+                if count >= max {
+                    self.modifiers.remove(index);
+                }
+                self.modifiers.push(modifier);
+            }
+            _ => self.modifiers.push(modifier),
+        }
+        self.dirty = true;
+    }
+
+    /// Remove all modifiers with the given name.
+    pub fn remove(&mut self, name: &str) {
+        self.modifiers.retain(|modifier| modifier.name != name);
+        self.dirty = true;
+    }
+
+    /// Remove all modifiers.
+    pub fn clear(&mut self) {
+        self.modifiers.clear();
+        self.dirty = true;
+    }
+
+    /// Get all active modifiers.
+    pub fn get_modifiers(&self) -> &[RangeModifier] {
+        &self.modifiers
+    }
+
+    /// Set the values of the range which are used before the modifiers are applied.
+    pub fn set_base(&mut self, start: f32, end: f32, change_per_second: f32) {
+        self.base = Some(RangeBaseValues {
+            start,
+            end,
+            change_per_second,
+        });
+        self.dirty = true;
+    }
+
+    /// Apply all modifiers of the target on the base value.
+    pub fn apply(&self, target: RangeModifierTarget, base: f32) -> f32 {
+        let mut flat = 0.0;
+        let mut percentage = 0.0;
+        for modifier in self.modifiers.iter().filter(|m| m.target == target) {
+            match modifier.kind {
+                RangeModifierKind::Flat(value) => flat += value,
+                RangeModifierKind::Percentage(value) => percentage += value,
+            }
+        }
+        (base + flat) * (1.0 + percentage)
+    }
+
+    /// Tick the durations of the modifiers and remove the expired ones.
+    ///
+    /// Returns true if a modifier expired.
+    fn tick(&mut self, delta: std::time::Duration) -> bool {
+        let count = self.modifiers.len();
+        self.modifiers
+            .retain_mut(|modifier| match modifier.duration.as_mut() {
+                Some(timer) => !timer.tick(delta).finished(),
+                None => true,
+            });
+        if count != self.modifiers.len() {
+            self.dirty = true;
+        }
+        self.dirty
+    }
+}
+
+/// A range component which remembers the value and limit it reported last.
+pub trait ReportedRange: Component {
+    /// Treat the current state as already reported so no events are sent for it.
+    fn reset_reported(&mut self);
+}
+
+impl<T: Send + Sync + 'static> ReportedRange for Range<T> {
+    fn reset_reported(&mut self) {
+        Self::reset_reported(self);
+    }
+}
+
+/// A system which prepares ranges which were added since the last run.
+///
+/// It is used for `Range` and `DiscreteRange`.  Ranges which were loaded from a scene
+/// do not know which value and limit were reported already, so their current state is
+/// treated as reported.
+pub fn initialize_added_ranges<R: ReportedRange>(mut range_query: Query<&mut R, Added<R>>) {
+    for mut range in range_query.iter_mut() {
+        range.reset_reported();
+    }
+}
+
+/// Set the limits and the change_per_second of a range and adjust its value.
+fn set_modified_limits<T>(
+    range: &mut Range<T>,
+    base: RangeBaseValues,
+    value_policy: RangeValuePolicy,
+) {
+    let proportion = if range.end > range.start {
+        (range.current - range.start) / (range.end - range.start)
+    } else {
+        1.0
+    };
+    range.set_limits(base.start, base.end);
+    range.set_change_per_second(base.change_per_second);
+    let value = match value_policy {
+        RangeValuePolicy::KeepProportion => base.start + (base.end - base.start) * proportion,
+        RangeValuePolicy::Clamp => range.current,
+    };
+    range.set(value);
+}
+
+/// Base values and value policy of the modifiers of an entity, used to restore the
+/// range when the RangeModifiers component is removed.
+#[derive(Default)]
+pub struct RangeModifierBases {
+    bases: bevy::utils::HashMap<Entity, (RangeBaseValues, RangeValuePolicy)>,
+}
+
+/// A system which applies the RangeModifiers on the ranges and removes expired modifiers.
+///
+/// Value changes which are caused by new limits are reported with the `Modifier` source.
+/// When the RangeModifiers component is removed, the base values are restored.
+#[allow(clippy::type_complexity)]
+pub fn update_range_modifiers<T: Send + Sync + 'static>(
+    mut range_query: Query<(Entity, &mut Range<T>, Option<&mut RangeModifiers<T>>)>,
+    mut removed_modifiers: RemovedComponents<RangeModifiers<T>>,
+    mut bases: Local<RangeModifierBases>,
+    time: Res<Time>,
+    mut event_writers: RangeEventWriters<T>,
+) {
+    for entity in removed_modifiers.read() {
+        let Some((base, value_policy)) = bases.bases.remove(&entity) else {
+            continue;
+        };
+        let Ok((_, mut range, None)) = range_query.get_mut(entity) else {
+            continue;
+        };
+        event_writers.report(entity, &mut range, RangeChangeSource::External);
+        set_modified_limits(&mut range, base, value_policy);
+        event_writers.report(entity, &mut range, RangeChangeSource::Modifier);
+    }
+
+    for (entity, mut range, modifiers) in range_query.iter_mut() {
+        let Some(mut modifiers) = modifiers else {
+            continue;
+        };
+        // Ticking the timers is no change of the modifiers, only expired ones are.
+        if !modifiers.bypass_change_detection().tick(time.delta()) {
+            continue;
+        }
+        modifiers.dirty = false;
+        event_writers.report(entity, &mut range, RangeChangeSource::External);
+
+        let base = match (modifiers.base, bases.bases.get(&entity)) {
+            (Some(base), _) => base,
+            (None, Some((base, _))) => *base,
+            (None, None) => RangeBaseValues {
+                start: range.start,
+                end: range.end,
+                change_per_second: range.change_per_second,
+            },
+        };
+        modifiers.base = Some(base);
+        bases.bases.insert(entity, (base, modifiers.value_policy));
+        let modified = RangeBaseValues {
+            start: modifiers.apply(RangeModifierTarget::Start, base.start),
+            end: modifiers.apply(RangeModifierTarget::End, base.end),
+            change_per_second: modifiers
+                .apply(RangeModifierTarget::ChangePerSecond, base.change_per_second),
+        };
+        set_modified_limits(&mut range, modified, modifiers.value_policy);
+        event_writers.report(entity, &mut range, RangeChangeSource::Modifier);
+    }
+}
+
+/// A system to update the range values based on their change_per_second attribute,
+/// the regeneration delay and the regeneration curve.
+///
+/// Changes which were done directly on the Range component since the last run are
+/// reported as RangeChangedEvent with the `External` source.  The limit events are
+/// only sent when a limit is entered and LimitLeftEvent when it is left.
 pub fn update_range<T: Send + Sync + 'static>(
     mut range_query: Query<(Entity, &mut Range<T>)>,
     time: Res<Time>,
-    mut start_range_limit_reached_event_writer: EventWriter<StartRangeLimitReachedEvent<T>>,
-    mut end_range_limit_reached_event_writer: EventWriter<EndRangeLimitReachedEvent<T>>,
+    mut event_writers: RangeEventWriters<T>,
 ) {
     for (entity, mut range) in range_query.iter_mut() {
-        let change_per_second = range.get_change_per_second();
-        match range.modify(change_per_second * time.delta_seconds()) {
-            ModifyRangeResult::Ok => {}
-            ModifyRangeResult::StartLimitReached { .. } => {
-                start_range_limit_reached_event_writer.send(StartRangeLimitReachedEvent {
-                    entity,
-                    _phantom: std::marker::PhantomData,
-                });
+        if let Some(time_since_decrease) = range.time_since_decrease.as_mut() {
+            *time_since_decrease += time.delta_seconds();
+        }
+        event_writers.report(entity, &mut range, RangeChangeSource::External);
+
+        let change_per_second = range.get_change_per_second() * range.get_regeneration_factor();
+        if change_per_second != 0.0 {
+            range.modify(change_per_second * time.delta_seconds());
+            event_writers.report(entity, &mut range, RangeChangeSource::Regeneration);
+        }
+    }
+}
+
+/// The position of a threshold in a range.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ThresholdMark {
+    /// An absolute value of the range.
+    Absolute(f32),
+
+    /// A fraction between the start and the end of the range.  0.25 is a
+    /// quarter of the way from the start to the end.
+    Percentage(f32),
+}
+
+/// The direction in which a threshold was crossed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ThresholdDirection {
+    /// The value was below the threshold and is now above.
+    Rising,
+
+    /// The value was above the threshold and is now below.
+    Falling,
+}
+
+/// A mark in a range which sends an event when the value crosses it.
+///
+/// The hysteresis prevents flickering if the value stays around the mark.  The value
+/// must move more than the hysteresis past the mark to count as crossed.  It uses the
+/// same unit as the mark, so it is a fraction of the range for percentage marks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeThreshold {
+    pub name: String,
+    pub mark: ThresholdMark,
+    pub hysteresis: f32,
+    above: Option<bool>,
+}
+
+impl RangeThreshold {
+    /// Create a new threshold without hysteresis.
+    pub fn new(name: impl Into<String>, mark: ThresholdMark) -> Self {
+        Self {
+            name: name.into(),
+            mark,
+            hysteresis: 0.0,
+            above: None,
+        }
+    }
+
+    /// Create a new threshold with the given hysteresis.
+    pub fn with_hysteresis(self, hysteresis: f32) -> Self {
+        Self { hysteresis, ..self }
+    }
+
+    /// Returns true if the value was above the mark the last time it was checked.
+    ///
+    /// It is None if the threshold was not checked yet.
+    pub fn is_above(&self) -> Option<bool> {
+        self.above
+    }
+
+    /// Check the value and return the direction if the threshold was crossed.
+    ///
+    /// The first check only stores on which side of the mark the value is.
+    fn check<T>(&mut self, range: &Range<T>) -> Option<ThresholdDirection> {
+        let length = range.get_end() - range.get_start();
+        let (mark, hysteresis) = match self.mark {
+            ThresholdMark::Absolute(mark) => (mark, self.hysteresis),
+            ThresholdMark::Percentage(fraction) => (
+                range.get_start() + length * fraction,
+                length * self.hysteresis,
+            ),
+        };
+        let value = range.get_raw();
+        match self.above {
+            None => {
+                self.above = Some(value >= mark);
+                None
             }
-            ModifyRangeResult::EndLimitReached { .. } => {
-                end_range_limit_reached_event_writer.send(EndRangeLimitReachedEvent {
+            Some(true) if value < mark - hysteresis => {
+                self.above = Some(false);
+                Some(ThresholdDirection::Falling)
+            }
+            Some(false) if value > mark + hysteresis => {
+                self.above = Some(true);
+                Some(ThresholdDirection::Rising)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A list of thresholds for the Range with the same type parameter.
+///
+/// # Examples
+/// ```rust
+/// use some_bevy_tools::range::*;
+///
+/// #[derive(Default)]
+/// struct Health;
+///
+/// let thresholds = RangeThresholds::<Health>::default().with_threshold(
+///     RangeThreshold::new("low_health", ThresholdMark::Percentage(0.25)).with_hysteresis(0.05),
+/// );
+/// assert_eq!(thresholds.get_thresholds().len(), 1);
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct RangeThresholds<T> {
+    thresholds: Vec<RangeThreshold>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> Default for RangeThresholds<T> {
+    fn default() -> Self {
+        Self {
+            thresholds: Vec::new(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> RangeThresholds<T> {
+    /// Create new thresholds with the given threshold added.
+    pub fn with_threshold(mut self, threshold: RangeThreshold) -> Self {
+        self.add(threshold);
+        self
+    }
+
+    /// Add a threshold.
+    pub fn add(&mut self, threshold: RangeThreshold) {
+        self.thresholds.push(threshold);
+    }
+
+    /// Remove all thresholds with the given name.
+    pub fn remove(&mut self, name: &str) {
+        self.thresholds.retain(|threshold| threshold.name != name);
+    }
+
+    /// Get all thresholds.
+    pub fn get_thresholds(&self) -> &[RangeThreshold] {
+        &self.thresholds
+    }
+}
+
+/// An event which is sent when the value of a range crosses a threshold.
+#[derive(Debug, Event)]
+pub struct RangeThresholdCrossedEvent<T> {
+    pub entity: Entity,
+    pub name: String,
+    pub direction: ThresholdDirection,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+/// A system which checks the RangeThresholds and sends RangeThresholdCrossedEvents.
+pub fn update_range_thresholds<T: Send + Sync + 'static>(
+    mut range_query: Query<(Entity, &Range<T>, &mut RangeThresholds<T>)>,
+    mut threshold_crossed_event_writer: EventWriter<RangeThresholdCrossedEvent<T>>,
+) {
+    for (entity, range, mut thresholds) in range_query.iter_mut() {
+        for threshold in thresholds.thresholds.iter_mut() {
+            if let Some(direction) = threshold.check(range) {
+                threshold_crossed_event_writer.send(RangeThresholdCrossedEvent {
                     entity,
+                    name: threshold.name.clone(),
+                    direction,
                     _phantom: std::marker::PhantomData,
                 });
             }
@@ -248,17 +1207,34 @@ pub fn update_range<T: Send + Sync + 'static>(
 /// Add support for `Range`s in your game.
 ///
 /// It will produce StartRangeLimitReachedEvent and EndRangeLimitReachedEvent when the
-/// range reaches a limit.  It will also update the Range component based on the change_per_second
-/// attribute.
+/// range reaches a limit, LimitLeftEvent when it leaves a limit and RangeChangedEvent
+/// when the value changes.  It will also update the Range component based on the
+/// change_per_second attribute, apply the RangeModifiers and check the RangeThresholds.
+///
+/// The Range type is registered for reflection, so it can be used in scenes.
 #[derive(Debug, Default)]
 pub struct RangePlugin<T> {
     _phantom: std::marker::PhantomData<T>,
 }
-impl<T: Send + Sync + 'static> Plugin for RangePlugin<T> {
+impl<T: TypePath + Send + Sync> Plugin for RangePlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_event::<StartRangeLimitReachedEvent<T>>()
+        app.register_type::<Range<T>>()
+            .register_type::<RegenerationCurve>()
+            .add_event::<RangeChangedEvent<T>>()
+            .add_event::<StartRangeLimitReachedEvent<T>>()
             .add_event::<EndRangeLimitReachedEvent<T>>()
-            .add_systems(Update, update_range::<T>);
+            .add_event::<LimitLeftEvent<T>>()
+            .add_event::<RangeThresholdCrossedEvent<T>>()
+            .add_systems(
+                Update,
+                (
+                    initialize_added_ranges::<Range<T>>,
+                    update_range_modifiers::<T>,
+                    update_range::<T>,
+                    update_range_thresholds::<T>,
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -266,10 +1242,20 @@ impl<T: Send + Sync + 'static> Plugin for RangePlugin<T> {
 mod tests {
     use super::*;
 
-    #[derive(Debug)]
+    #[derive(Debug, Default, TypePath)]
     struct Health;
     type HealthRange = Range<Health>;
 
+    #[test]
+    fn test_range_equality_ignores_report_state() {
+        let configured = HealthRange::new(0.0, 10.0).with_current(5.0);
+        let mut modified = HealthRange::new(0.0, 10.0);
+        modified.set(5.0);
+        assert_eq!(configured, modified);
+        modified.set_change_per_second(1.0);
+        assert_ne!(configured, modified);
+    }
+
     #[test]
     fn test_range_basic_getter_and_setter() {
         let mut range = HealthRange::new(0.0, 10.0);
@@ -367,4 +1353,451 @@ mod tests {
         assert_eq!(range.get_quantize(), 0.5);
         assert_eq!(range.get_change_per_second(), 1.5);
     }
+
+    fn range_test_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(RangePlugin::<Health>::default());
+        app
+    }
+
+    fn read_changed_events(app: &mut App) -> Vec<(f32, f32, f32, RangeChangeSource)> {
+        app.world
+            .resource_mut::<Events<RangeChangedEvent<Health>>>()
+            .drain()
+            .map(|event| (event.previous, event.current, event.delta, event.source))
+            .collect()
+    }
+
+    #[test]
+    fn test_range_changed_event_on_external_change() {
+        let mut app = range_test_app();
+        let entity = app.world.spawn(HealthRange::new(0.0, 10.0)).id();
+        app.update();
+        assert!(read_changed_events(&mut app).is_empty());
+
+        app.world
+            .get_mut::<HealthRange>(entity)
+            .unwrap()
+            .modify(-3.0);
+        app.update();
+        assert_eq!(
+            read_changed_events(&mut app),
+            vec![(10.0, 7.0, -3.0, RangeChangeSource::External)]
+        );
+
+        app.update();
+        assert!(read_changed_events(&mut app).is_empty());
+    }
+
+    #[test]
+    fn test_range_changed_event_on_regeneration() {
+        let mut app = range_test_app();
+        app.world
+            .spawn(HealthRange::new(0.0, 10.0).with_change_per_second(-2.0));
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(0.5));
+        app.update();
+        assert_eq!(
+            read_changed_events(&mut app),
+            vec![(10.0, 9.0, -1.0, RangeChangeSource::Regeneration)]
+        );
+    }
+
+    #[test]
+    fn test_range_writer_reports_once() {
+        fn apply_damage(mut ranges: RangeWriter<Health>, query: Query<Entity, With<HealthRange>>) {
+            for entity in query.iter() {
+                ranges.modify(entity, -4.0);
+            }
+        }
+
+        let mut app = range_test_app();
+        app.world
+            .spawn(HealthRange::new(0.0, 10.0).with_current(5.0));
+        app.add_systems(Update, apply_damage.before(update_range::<Health>));
+        app.update();
+        assert_eq!(
+            read_changed_events(&mut app),
+            vec![(5.0, 1.0, -4.0, RangeChangeSource::External)]
+        );
+
+        app.update();
+        assert_eq!(
+            read_changed_events(&mut app),
+            vec![(1.0, 0.0, -1.0, RangeChangeSource::External)]
+        );
+        assert_eq!(
+            app.world
+                .resource::<Events<StartRangeLimitReachedEvent<Health>>>()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_range_limit_events_are_edge_triggered() {
+        let mut app = range_test_app();
+        let entity = app
+            .world
+            .spawn(
+                HealthRange::new(0.0, 10.0)
+                    .with_current(1.0)
+                    .with_change_per_second(-4.0),
+            )
+            .id();
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(0.5));
+        let mut start_events = 0;
+        for _ in 0..3 {
+            app.update();
+            start_events += app
+                .world
+                .resource_mut::<Events<StartRangeLimitReachedEvent<Health>>>()
+                .drain()
+                .count();
+        }
+        assert_eq!(start_events, 1);
+        assert_eq!(
+            app.world.get::<HealthRange>(entity).unwrap().get_limit(),
+            Some(RangeLimit::Start)
+        );
+
+        app.world.get_mut::<HealthRange>(entity).unwrap().set(5.0);
+        app.update();
+        let left_events: Vec<RangeLimit> = app
+            .world
+            .resource_mut::<Events<LimitLeftEvent<Health>>>()
+            .drain()
+            .map(|event| event.limit)
+            .collect();
+        assert_eq!(left_events, vec![RangeLimit::Start]);
+        assert_eq!(
+            app.world.get::<HealthRange>(entity).unwrap().get_limit(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_range_modifiers_stacking() {
+        let modifier = |stacking| {
+            RangeModifier::new(
+                "buff",
+                RangeModifierTarget::End,
+                RangeModifierKind::Flat(5.0),
+            )
+            .with_stacking(stacking)
+        };
+        let mut modifiers = RangeModifiers::<Health>::default();
+        modifiers.add(modifier(RangeModifierStacking::Stack));
+        modifiers.add(modifier(RangeModifierStacking::Stack));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 20.0);
+
+        modifiers.add(modifier(RangeModifierStacking::StackUpTo(2)));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 20.0);
+
+        modifiers.add(modifier(RangeModifierStacking::StackUpTo(0)));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 20.0);
+
+        modifiers.add(modifier(RangeModifierStacking::Refresh));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 20.0);
+
+        modifiers.remove("buff");
+        modifiers.add(modifier(RangeModifierStacking::Replace));
+        modifiers.add(modifier(RangeModifierStacking::Replace));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 15.0);
+
+        modifiers.add(RangeModifier::new(
+            "percentage",
+            RangeModifierTarget::End,
+            RangeModifierKind::Percentage(-0.5),
+        ));
+        assert_eq!(modifiers.apply(RangeModifierTarget::End, 10.0), 7.5);
+        assert_eq!(modifiers.apply(RangeModifierTarget::Start, 10.0), 10.0);
+    }
+
+    #[test]
+    fn test_range_modifiers_apply_and_expire() {
+        let mut app = range_test_app();
+        let mut modifiers = RangeModifiers::<Health>::default();
+        modifiers.add(
+            RangeModifier::new(
+                "max_health",
+                RangeModifierTarget::End,
+                RangeModifierKind::Percentage(0.5),
+            )
+            .with_duration(1.0),
+        );
+        let entity = app
+            .world
+            .spawn((HealthRange::new(0.0, 100.0).with_current(50.0), modifiers))
+            .id();
+        app.update();
+        let range = app.world.get::<HealthRange>(entity).unwrap();
+        assert_eq!(range.get_end(), 150.0);
+        assert_eq!(range.get(), 75.0);
+        assert_eq!(
+            read_changed_events(&mut app),
+            vec![(50.0, 75.0, 25.0, RangeChangeSource::Modifier)]
+        );
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(1.0));
+        app.update();
+        let range = app.world.get::<HealthRange>(entity).unwrap();
+        assert_eq!(range.get_end(), 100.0);
+        assert_eq!(range.get(), 50.0);
+        assert!(app
+            .world
+            .get::<RangeModifiers<Health>>(entity)
+            .unwrap()
+            .get_modifiers()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_range_modifiers_ticking_is_no_change() {
+        #[derive(Resource, Default)]
+        struct ChangedFrames(usize);
+
+        let mut app = range_test_app();
+        app.init_resource::<ChangedFrames>().add_systems(
+            Update,
+            (|query: Query<(), Changed<RangeModifiers<Health>>>,
+              mut frames: ResMut<ChangedFrames>| {
+                frames.0 += query.iter().count();
+            })
+            .after(update_range_modifiers::<Health>),
+        );
+        let mut modifiers = RangeModifiers::<Health>::default();
+        modifiers.add(
+            RangeModifier::new(
+                "max_health",
+                RangeModifierTarget::End,
+                RangeModifierKind::Flat(10.0),
+            )
+            .with_duration(1.0),
+        );
+        app.world.spawn((HealthRange::new(0.0, 100.0), modifiers));
+        for _ in 0..3 {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(std::time::Duration::from_secs_f32(0.25));
+            app.update();
+        }
+        assert_eq!(app.world.resource::<ChangedFrames>().0, 1);
+
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(0.25));
+        app.update();
+        assert_eq!(app.world.resource::<ChangedFrames>().0, 2);
+    }
+
+    #[test]
+    fn test_range_modifiers_removed() {
+        let mut app = range_test_app();
+        let mut modifiers = RangeModifiers::<Health>::default();
+        modifiers.add(RangeModifier::new(
+            "max_health",
+            RangeModifierTarget::End,
+            RangeModifierKind::Flat(50.0),
+        ));
+        modifiers.add(RangeModifier::new(
+            "regeneration",
+            RangeModifierTarget::ChangePerSecond,
+            RangeModifierKind::Flat(2.0),
+        ));
+        let entity = app
+            .world
+            .spawn((HealthRange::new(0.0, 100.0).with_current(50.0), modifiers))
+            .id();
+        app.update();
+        let range = app.world.get::<HealthRange>(entity).unwrap();
+        assert_eq!(range.get_end(), 150.0);
+        assert_eq!(range.get_change_per_second(), 2.0);
+
+        app.world
+            .entity_mut(entity)
+            .remove::<RangeModifiers<Health>>();
+        app.update();
+        let range = app.world.get::<HealthRange>(entity).unwrap();
+        assert_eq!(range.get_end(), 100.0);
+        assert_eq!(range.get(), 50.0);
+        assert_eq!(range.get_change_per_second(), 0.0);
+    }
+
+    #[test]
+    fn test_range_exponential_regeneration_is_finite() {
+        for rate in [0.0, -1.0, 2.0] {
+            let curve = RegenerationCurve::Exponential { rate };
+            assert!(curve.sample(f32::INFINITY).is_finite(), "rate {rate}");
+            assert!(curve.sample(1.0).is_finite(), "rate {rate}");
+        }
+        assert_eq!(
+            RegenerationCurve::Exponential { rate: 0.0 }.sample(f32::INFINITY),
+            1.0
+        );
+
+        let mut app = range_test_app();
+        let entity = app
+            .world
+            .spawn(
+                HealthRange::new(0.0, 10.0)
+                    .with_current(5.0)
+                    .with_change_per_second(1.0)
+                    .with_regeneration_curve(RegenerationCurve::Exponential { rate: 0.0 }),
+            )
+            .id();
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(1.0));
+        app.update();
+        assert_eq!(app.world.get::<HealthRange>(entity).unwrap().get_raw(), 6.0);
+    }
+
+    #[test]
+    fn test_range_regeneration_delay() {
+        let mut app = range_test_app();
+        let entity = app
+            .world
+            .spawn(
+                HealthRange::new(0.0, 10.0)
+                    .with_current(5.0)
+                    .with_change_per_second(1.0)
+                    .with_regeneration_delay(1.0),
+            )
+            .id();
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(0.5));
+        app.update();
+        assert_eq!(app.world.get::<HealthRange>(entity).unwrap().get(), 6.0);
+
+        app.world
+            .get_mut::<HealthRange>(entity)
+            .unwrap()
+            .modify(-2.0);
+        app.update();
+        app.update();
+        assert_eq!(app.world.get::<HealthRange>(entity).unwrap().get_raw(), 3.5);
+        app.update();
+        assert_eq!(app.world.get::<HealthRange>(entity).unwrap().get_raw(), 4.0);
+    }
+
+    #[test]
+    fn test_range_reflect_roundtrip() {
+        use bevy::reflect::serde::{ReflectSerializer, UntypedReflectDeserializer};
+        use serde::de::DeserializeSeed;
+
+        let app = range_test_app();
+        let registry = app.world.resource::<AppTypeRegistry>().read();
+        let range = HealthRange::new(0.0, 10.0)
+            .with_current(4.0)
+            .with_change_per_second(0.5)
+            .with_regeneration_delay(2.0)
+            .with_regeneration_curve(RegenerationCurve::EaseIn { duration: 1.0 });
+
+        let serialized = ron::to_string(&ReflectSerializer::new(&range, &registry)).unwrap();
+        let reflected = UntypedReflectDeserializer::new(&registry)
+            .deserialize(&mut ron::Deserializer::from_str(&serialized).unwrap())
+            .unwrap();
+        let deserialized = HealthRange::from_reflect(reflected.as_ref()).unwrap();
+        assert_eq!(deserialized.get_start(), 0.0);
+        assert_eq!(deserialized.get_end(), 10.0);
+        assert_eq!(deserialized.get_raw(), 4.0);
+        assert_eq!(deserialized.get_change_per_second(), 0.5);
+        assert_eq!(deserialized.get_regeneration_delay(), 2.0);
+        assert_eq!(
+            deserialized.get_regeneration_curve(),
+            &RegenerationCurve::EaseIn { duration: 1.0 }
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_range_dynamic_scene_roundtrip() {
+        use bevy::ecs::entity::EntityHashMap;
+        use bevy::scene::{serde::SceneDeserializer, DynamicScene};
+        use serde::de::DeserializeSeed;
+
+        let mut app = range_test_app();
+        let entity = app
+            .world
+            .spawn(
+                HealthRange::new(0.0, 10.0)
+                    .with_current(4.0)
+                    .with_change_per_second(0.5),
+            )
+            .id();
+        let registry = app.world.resource::<AppTypeRegistry>().clone();
+        let serialized = DynamicScene::from_world(&app.world)
+            .serialize_ron(&registry)
+            .unwrap();
+
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut ron::Deserializer::from_str(&serialized).unwrap())
+        .unwrap();
+        let mut world = World::new();
+        world.insert_resource(registry);
+        let mut entity_map = EntityHashMap::default();
+        scene.write_to_world(&mut world, &mut entity_map).unwrap();
+
+        let range = world.get::<HealthRange>(entity_map[&entity]).unwrap();
+        assert_eq!(range.get_start(), 0.0);
+        assert_eq!(range.get_end(), 10.0);
+        assert_eq!(range.get_raw(), 4.0);
+        assert_eq!(range.get_change_per_second(), 0.5);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_range_deserialize_from_ron() {
+        let range: HealthRange =
+            ron::from_str("(start: 0.0, end: 20.0, current: 5.0, change_per_second: 1.0)").unwrap();
+        assert_eq!(range.get_end(), 20.0);
+        assert_eq!(range.get(), 5.0);
+        assert_eq!(range.get_quantize(), 1.0);
+        assert_eq!(range.get_change_per_second(), 1.0);
+        assert_eq!(range.get_regeneration_curve(), &RegenerationCurve::Linear);
+    }
+
+    #[test]
+    fn test_range_threshold_with_hysteresis() {
+        let mut app = range_test_app();
+        let entity = app
+            .world
+            .spawn((
+                HealthRange::new(0.0, 100.0),
+                RangeThresholds::<Health>::default().with_threshold(
+                    RangeThreshold::new("low_health", ThresholdMark::Percentage(0.25))
+                        .with_hysteresis(0.05),
+                ),
+            ))
+            .id();
+        let mut crossings = Vec::new();
+        for value in [100.0, 24.0, 19.0, 27.0, 29.0, 31.0, 22.0, 10.0] {
+            app.world.get_mut::<HealthRange>(entity).unwrap().set(value);
+            app.update();
+            crossings.extend(
+                app.world
+                    .resource_mut::<Events<RangeThresholdCrossedEvent<Health>>>()
+                    .drain()
+                    .map(|event| (value, event.direction)),
+            );
+        }
+        assert_eq!(
+            crossings,
+            vec![
+                (19.0, ThresholdDirection::Falling),
+                (31.0, ThresholdDirection::Rising),
+                (10.0, ThresholdDirection::Falling),
+            ]
+        );
+    }
 }
@@ -17,8 +17,14 @@ pub struct ThirdPartyController {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum CharacterControllerEvent {
+    /// Turn the camera by the slider value.  Used for per frame deltas like the mouse.
     Turn,
+    /// Turn the camera by the slider value per second.  Used for gamepad sticks
+    /// which report their position every frame.
+    TurnPerSecond,
     IncreaseCameraDistance,
     DecreaseCameraDistance,
     MoveForward,
@@ -55,13 +61,45 @@ pub fn default_character_controller_event_mapping() -> input::InputMapping<Chara
                 input::UserButtonInput::KeyPressed(KeyCode::KeyD),
                 CharacterControllerEvent::MoveRight,
             ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadUp),
+                CharacterControllerEvent::MoveForward,
+            ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadDown),
+                CharacterControllerEvent::MoveBackward,
+            ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadLeft),
+                CharacterControllerEvent::MoveLeft,
+            ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::DPadRight),
+                CharacterControllerEvent::MoveRight,
+            ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::LeftTrigger),
+                CharacterControllerEvent::IncreaseCameraDistance,
+            ),
+            (
+                input::UserButtonInput::GamepadButtonPressed(GamepadButtonType::RightTrigger),
+                CharacterControllerEvent::DecreaseCameraDistance,
+            ),
+        ],
+        [
+            (
+                SliderMappingType::Mouse(input::MouseSlider::default()),
+                CharacterControllerEvent::Turn,
+                0.005,
+                -0.005,
+            ),
+            (
+                SliderMappingType::GamepadStick(input::GamepadSide::Right, 0.1),
+                CharacterControllerEvent::TurnPerSecond,
+                3.0,
+                3.0,
+            ),
         ],
-        [(
-            SliderMappingType::MouseMove(10.0),
-            CharacterControllerEvent::Turn,
-            0.005,
-            -0.005,
-        )],
     ))
 }
 
@@ -72,6 +110,7 @@ pub fn third_party_camera_controller_system(
         &ThirdPartyController,
         &mut third_party_camera::ThirdPartyCamera,
     )>,
+    time: Res<Time>,
 ) {
     for ev in action_events.read() {
         match ev.action {
@@ -94,9 +133,14 @@ pub fn third_party_camera_controller_system(
     }
 
     for ev in slider_events.read() {
+        let turn = match ev.action {
+            CharacterControllerEvent::Turn => Vec2::new(ev.x, ev.y),
+            CharacterControllerEvent::TurnPerSecond => Vec2::new(ev.x, ev.y) * time.delta_seconds(),
+            _ => continue,
+        };
         for (_controller, mut camera) in third_party_query.iter_mut() {
-            camera.rotate_y -= ev.x;
-            camera.rotate_x = (camera.rotate_x - ev.y).clamp(-PI / 2.0 + 0.01, PI / 2.0 - 0.01);
+            camera.rotate_y -= turn.x;
+            camera.rotate_x = (camera.rotate_x - turn.y).clamp(-PI / 2.0 + 0.01, PI / 2.0 - 0.01);
         }
     }
 }
@@ -174,6 +218,91 @@ impl Plugin for ThirdPartyControllerPlugin {
             input::InputMappingPlugin::<CharacterControllerEvent>::default(),
         ))
         .insert_resource(default_character_controller_event_mapping())
-        .add_systems(Update, third_party_camera_controller_system);
+        .add_systems(
+            Update,
+            third_party_camera_controller_system.after(input::InputMappingSystem),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{InputRecording, InputReplay, RecordedFrame};
+
+    /// Create an app which replays the frames and a camera with a ThirdPartyController.
+    fn replay_test_app(frames: Vec<RecordedFrame<CharacterControllerEvent>>) -> (App, Entity) {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(bevy::input::InputPlugin)
+            .add_plugins(ThirdPartyControllerPlugin)
+            .insert_resource(InputReplay::new(InputRecording { frames }));
+        let target = app.world.spawn(Transform::default()).id();
+        let camera = app
+            .world
+            .spawn((
+                ThirdPartyController {
+                    min_distance: 2.0,
+                    max_distance: 10.0,
+                },
+                third_party_camera::ThirdPartyCamera {
+                    target,
+                    distance: 5.0,
+                    rotate_y: 0.0,
+                    rotate_x: 0.0,
+                },
+                Transform::default(),
+            ))
+            .id();
+        (app, camera)
+    }
+
+    #[test]
+    fn third_party_controller_replay() {
+        let (mut app, camera) = replay_test_app(vec![RecordedFrame {
+            frame: 0,
+            actions: vec![(CharacterControllerEvent::IncreaseCameraDistance, 1.0)],
+            sliders: vec![(CharacterControllerEvent::Turn, Vec2::new(0.5, 0.25))],
+        }]);
+        for _ in 0..3 {
+            app.update();
+        }
+        let camera = app
+            .world
+            .get::<third_party_camera::ThirdPartyCamera>(camera)
+            .unwrap();
+        assert_eq!(camera.distance, 4.0);
+        assert_eq!(camera.rotate_y, -0.5);
+        assert_eq!(camera.rotate_x, -0.25);
+    }
+
+    fn turn_per_second_rotation(frames: u32, delta: f32) -> f32 {
+        let (mut app, camera) = replay_test_app(
+            (0..frames)
+                .map(|frame| RecordedFrame {
+                    frame,
+                    actions: vec![],
+                    sliders: vec![(CharacterControllerEvent::TurnPerSecond, Vec2::X)],
+                })
+                .collect(),
+        );
+        for _ in 0..frames {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(std::time::Duration::from_secs_f32(delta));
+            app.update();
+        }
+        app.world
+            .get::<third_party_camera::ThirdPartyCamera>(camera)
+            .unwrap()
+            .rotate_y
+    }
+
+    #[test]
+    fn third_party_controller_stick_turn_is_frame_rate_independent() {
+        let slow = turn_per_second_rotation(10, 0.1);
+        let fast = turn_per_second_rotation(40, 0.025);
+        assert!((slow + 1.0).abs() < 1e-4, "rotation was {slow}");
+        assert!((slow - fast).abs() < 1e-4, "{slow} != {fast}");
     }
 }